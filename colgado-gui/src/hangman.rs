@@ -0,0 +1,140 @@
+use colgado_logic::models::game_view::{GameOutcome, GameView};
+use iced::{
+    mouse,
+    widget::canvas::{self, Frame, Geometry, LineCap, Path, Stroke},
+    Point, Rectangle, Renderer, Theme,
+};
+
+/// Head, body, arms and legs
+const BODY_PARTS: usize = 6;
+/// Distance from the feet of the hanged figure to the ground
+const DROP: f32 = 0.2;
+
+/// Gallows drawn as the lives are lost, the figure is freed when the word is guessed
+#[derive(Clone, Copy, Debug)]
+pub struct Hangman {
+    failures: usize,
+    max_lives: usize,
+    outcome: GameOutcome,
+}
+
+impl Hangman {
+    pub fn new(game: &GameView) -> Self {
+        Self {
+            failures: game.max_lives.saturating_sub(game.lives),
+            max_lives: game.max_lives,
+            outcome: game.outcome,
+        }
+    }
+
+    /// Parts of the figure drawn, spread over the lives so the last one completes it
+    fn body_parts(&self) -> usize {
+        if self.outcome != GameOutcome::InProgress || self.max_lives == 0 {
+            return BODY_PARTS;
+        }
+        (self.failures * BODY_PARTS)
+            .div_ceil(self.max_lives)
+            .min(BODY_PARTS)
+    }
+}
+
+impl<Message> canvas::Program<Message> for Hangman {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let palette = theme.extended_palette();
+        let color = match self.outcome {
+            GameOutcome::InProgress => palette.background.base.text,
+            GameOutcome::Won => palette.success.base.color,
+            GameOutcome::Lost => palette.danger.base.color,
+        };
+        let side = bounds.width.min(bounds.height);
+        let stroke = Stroke::default()
+            .with_color(color)
+            .with_width((side / 40.0).max(2.0))
+            .with_line_cap(LineCap::Round);
+        let mut pencil = Pencil {
+            frame: &mut frame,
+            stroke,
+            side,
+            left: (bounds.width - side) / 2.0,
+            top: (bounds.height - side) / 2.0,
+        };
+
+        // base, post, beam and brace
+        pencil.line((0.1, 0.95), (0.6, 0.95));
+        pencil.line((0.25, 0.95), (0.25, 0.05));
+        pencil.line((0.25, 0.05), (0.7, 0.05));
+        pencil.line((0.25, 0.2), (0.4, 0.05));
+        match self.outcome {
+            GameOutcome::Won => pencil.figure(0.82, DROP, BODY_PARTS, true),
+            _ => {
+                pencil.line((0.7, 0.05), (0.7, 0.2));
+                pencil.figure(0.7, 0.0, self.body_parts(), false);
+            }
+        }
+        if self.outcome == GameOutcome::Lost {
+            pencil.cross((0.675, 0.27));
+            pencil.cross((0.725, 0.27));
+        }
+        vec![frame.into_geometry()]
+    }
+}
+
+/// Draws on a square of side 1 centered in the canvas
+struct Pencil<'a, 'b> {
+    frame: &'a mut Frame,
+    stroke: Stroke<'b>,
+    side: f32,
+    left: f32,
+    top: f32,
+}
+
+impl Pencil<'_, '_> {
+    fn point(&self, (x, y): (f32, f32)) -> Point {
+        Point::new(self.left + x * self.side, self.top + y * self.side)
+    }
+
+    fn line(&mut self, from: (f32, f32), to: (f32, f32)) {
+        let path = Path::line(self.point(from), self.point(to));
+        self.frame.stroke(&path, self.stroke);
+    }
+
+    fn circle(&mut self, center: (f32, f32), radius: f32) {
+        let path = Path::circle(self.point(center), radius * self.side);
+        self.frame.stroke(&path, self.stroke);
+    }
+
+    fn cross(&mut self, (x, y): (f32, f32)) {
+        let size = 0.015;
+        self.line((x - size, y - size), (x + size, y + size));
+        self.line((x - size, y + size), (x + size, y - size));
+    }
+
+    /// Figure hanging from the rope moved `down`, with the arms raised when celebrating
+    fn figure(&mut self, x: f32, down: f32, parts: usize, arms_up: bool) {
+        let y = |y: f32| y + down;
+        let arm_end = if arms_up { y(0.32) } else { y(0.52) };
+        let lines = [
+            ((x, y(0.36)), (x, y(0.6))),
+            ((x, y(0.42)), (x - 0.1, arm_end)),
+            ((x, y(0.42)), (x + 0.1, arm_end)),
+            ((x, y(0.6)), (x - 0.08, y(0.75))),
+            ((x, y(0.6)), (x + 0.08, y(0.75))),
+        ];
+        if parts > 0 {
+            self.circle((x, y(0.28)), 0.08);
+        }
+        for (from, to) in lines.into_iter().take(parts.saturating_sub(1)) {
+            self.line(from, to);
+        }
+    }
+}
@@ -1 +1,3 @@
+pub mod hangman;
+pub mod settings;
 pub mod tasks;
@@ -1,29 +1,42 @@
 #![windows_subsystem = "windows"]
-use std::{
-    future::Future,
-    sync::{
-        atomic::{AtomicBool, Ordering},
-        Arc,
-    },
-};
+use std::{fmt, future::Future, sync::Arc};
 
-use colgado_gui::tasks::TaskBuider;
+use colgado_gui::{
+    hangman::Hangman,
+    settings::{SettingsField, SettingsForm},
+    tasks::TaskBuider,
+};
 use colgado_logic::{
     actors::game_actor::TwitchGameHandle,
+    announcements::Announcement,
+    config::{Config, CONFIG_FILE},
     errors::ColgadoLogicError,
-    models::{game_view::GameView, handles::Handles},
+    i18n::{Catalog, Language},
+    models::{
+        events::{ConnectionStatus, GameEvent},
+        game_view::{GameOutcome, GameView},
+        handles::Handles,
+        leaderboard::Leaderboard,
+    },
+    settings::Settings,
+    word_bank::RandomWord,
 };
 use iced::{
-    widget::{self, button, center, column, row, text, text_input, Column},
+    futures::{stream, Stream},
+    widget::{
+        self, button, canvas, center, checkbox, column, pick_list, row, scrollable, text,
+        text_input, Column,
+    },
     window::{self, close_requests},
     Alignment::Center,
     Element, Font,
     Length::Fill,
     Subscription, Task, Theme,
 };
+use tokio::sync::broadcast::error::RecvError;
 pub type ClonableResult<T, E> = Result<T, Arc<E>>;
 pub type LogicResult<T> = ClonableResult<T, ColgadoLogicError>;
-type ConnectedTuple = (Handles, Arc<[tokio::task::JoinHandle<()>]>, Box<str>);
+type ConnectedTuple = (Handles, Arc<[tokio::task::JoinHandle<()>]>);
 
 pub const FONT: &[u8] =
     include_bytes!("../assets/fonts/RobotoMonoNerdFontMono-Regular.ttf").as_slice();
@@ -32,38 +45,51 @@ pub const TEXT: Font = Font::with_name("RobotoMono Nerd Font Mono");
 pub const ICON: &[u8] = include_bytes!("../assets/logo.png");
 
 fn main() -> iced::Result {
-    let application = iced::application(
-        "El que tengo aquí colgado",
-        ColgadoApp::update,
-        ColgadoApp::view,
-    )
-    .window(window::Settings {
-        icon: Some(
-            window::icon::from_file_data(include_bytes!("../assets/logo.png"), None).unwrap(),
-        ),
+    let application = iced::daemon(ColgadoApp::title, ColgadoApp::update, ColgadoApp::view)
+        .default_font(TEXT)
+        .font(FONT)
+        .theme(|_, _| system_theme_mode())
+        .subscription(ColgadoApp::subscription)
+        .antialiasing(true);
+    application.run_with(ColgadoApp::new)
+}
+
+fn window_settings() -> window::Settings {
+    window::Settings {
+        icon: Some(window::icon::from_file_data(ICON, None).unwrap()),
+        position: window::Position::Centered,
+        exit_on_close_request: false,
         ..Default::default()
-    })
-    .default_font(TEXT)
-    .font(FONT)
-    .theme(|_| system_theme_mode())
-    .subscription(ColgadoApp::subscription)
-    .antialiasing(true)
-    .centered()
-    .exit_on_close_request(false);
-    application.run()
+    }
 }
 
 #[derive(Clone, Debug)]
 pub enum Message {
+    UiLanguage(LogicResult<Language>),
     NewConnection,
     Connected(LogicResult<ConnectedTuple>),
     NewGame,
     NewWord(String),
-    WordSetted(String),
+    WordSetted(LogicResult<String>),
     SubmitWord,
-    GetActualState,
+    Categories(LogicResult<Vec<String>>),
+    CategorySelected(CategoryChoice),
+    RandomWord,
+    RandomWordSetted(LogicResult<RandomWord>),
+    TogglePeek,
+    ToggleViewer,
     ActualState(Option<GameView>),
+    GameEvent(GameEvent),
+    Reconnect,
+    OpenSettings,
+    SettingsLoaded(LogicResult<Settings>),
+    SettingsEdited(SettingsField),
+    SaveSettings,
+    SettingsSaved(LogicResult<Settings>),
+    CloseSettings,
     Close(window::Id),
+    /// The actors have stopped, or did not in time
+    ShutDown(LogicResult<()>),
     None,
 }
 
@@ -75,25 +101,97 @@ pub enum State {
     SettingGame,
     Playing,
     GameCompleted,
+    /// Form with the options of `env.toml`, the game keeps running behind it
+    Settings,
+    /// The actors can not continue, a new connection is needed
+    Error,
+}
+
+/// Category of the random words, `Any` picks from all of them and carries its label
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CategoryChoice {
+    Any(&'static str),
+    Category(String),
+}
+
+impl CategoryChoice {
+    fn any(ui: Catalog) -> Self {
+        CategoryChoice::Any(ui.text("ui.any-category"))
+    }
+}
+
+impl fmt::Display for CategoryChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CategoryChoice::Any(label) => write!(f, "{label}"),
+            CategoryChoice::Category(name) => write!(f, "{name}"),
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
 pub struct ColgadoApp {
     game: GameView,
+    /// Word being typed, never shown in the viewer window
+    secret: String,
+    /// Shows the secret instead of masking it
+    peek: bool,
+    /// Categories of the word bank, empty when there are no words
+    categories: Vec<CategoryChoice>,
+    category: CategoryChoice,
+    /// Window with the controls, closing it closes the app
+    control_window: Option<window::Id>,
+    /// Window without the secret to capture in the stream
+    viewer_window: Option<window::Id>,
+    leaderboard: Leaderboard,
+    connection: Option<ConnectionStatus>,
+    /// Last error, shown until the next one or a new connection
+    error: Option<Arc<ColgadoLogicError>>,
     state: State,
     tasks: Option<Arc<[tokio::task::JoinHandle<()>]>>,
     handles: TaskCreator,
-    closing: Arc<AtomicBool>,
-    command: Option<Box<str>>,
+    settings: SettingsForm,
+    /// Texts of the windows
+    ui: Catalog,
     view: ColgadoView,
 }
 
 impl ColgadoApp {
-    pub fn new() -> Self {
-        Self::default()
+    pub fn new() -> (Self, Task<Message>) {
+        let (control_window, open) = window::open(window_settings());
+        let app = Self {
+            control_window: Some(control_window),
+            ..Self::default()
+        };
+        let language = async {
+            Config::load(CONFIG_FILE)
+                .await
+                .map(|config| config.ui_language())
+        };
+        let language = TaskBuider::default()
+            .set_closure(language)
+            .set_mapping(Message::UiLanguage)
+            .err_to_arc()
+            .perform();
+        (app, Task::batch([open.map(|_| Message::None), language]))
     }
+
+    pub fn title(&self, id: window::Id) -> String {
+        let title = self.ui.text("ui.title");
+        if Some(id) == self.viewer_window {
+            self.ui.format("ui.viewer-title", &[("title", &title)])
+        } else {
+            title.to_owned()
+        }
+    }
+
     pub fn update(&mut self, message: Message) -> Task<Message> {
         match message {
+            Message::UiLanguage(Ok(language)) => {
+                self.set_ui_language(language);
+            }
+            // the connection reports the same error, the default language is kept until then
+            Message::UiLanguage(Err(_)) => {}
             Message::NewConnection => {
                 if let State::NewConnection = self.state {
                     self.state = State::Connecting;
@@ -105,99 +203,257 @@ impl ColgadoApp {
                     Ok(value) => value,
                     Err(err) => {
                         eprintln!("{err}");
+                        self.error = Some(err);
                         self.state = State::NewConnection;
                         return Task::none();
                     }
                 };
-                let (handles, tasks, command) = ok_value;
+                let (handles, tasks) = ok_value;
                 self.state = State::NewWord;
+                self.error = None;
+                self.connection = Some(ConnectionStatus::Connected);
                 self.handles = TaskCreator::new(handles);
                 self.tasks = Some(tasks);
-                self.command = Some(command);
+                return self.handles.get_categories();
             }
             Message::NewGame => {
                 self.state = State::NewWord;
                 self.game = GameView::default();
             }
             Message::NewWord(word) => {
-                self.game.word = word;
+                self.secret = word;
             }
-            Message::SubmitWord => {
-                if !self.game.word.is_empty() {
-                    self.state = State::SettingGame;
-                    return self.send_new_word();
-                }
+            Message::TogglePeek => {
+                self.peek = !self.peek;
             }
-            Message::WordSetted(word) => {
-                self.game.word = word;
-                self.game.is_completed = false;
-                self.state = State::Playing;
-                let message = "Comenzando partida";
-                let message = match &self.command {
-                    Some(command) =>
-                        format!("{message}, escribe el comando {command} seguido de la palabra (Si solo es una letra puedes omitir el comando)"
-                        ),
-                    None => message.to_string(),
-                };
-                return self.send_message(message);
+            Message::ToggleViewer => {
+                return self.toggle_viewer();
+            }
+            Message::SubmitWord if !self.secret.is_empty() => {
+                self.state = State::SettingGame;
+                return self.send_new_word();
+            }
+            Message::WordSetted(Err(err)) => {
+                self.state = State::NewWord;
+                self.show_error(err);
             }
-            Message::GetActualState => {
-                if !self.game.is_completed {
-                    return self.get_game();
+            Message::WordSetted(Ok(word)) => {
+                self.start_round(word);
+            }
+            Message::Categories(Ok(categories)) => {
+                self.categories = categories
+                    .into_iter()
+                    .map(CategoryChoice::Category)
+                    .collect();
+                if !self.categories.is_empty() {
+                    self.categories.insert(0, CategoryChoice::any(self.ui));
                 }
-                if let State::Playing = self.state {
-                    self.state = State::GameCompleted;
-                    let message = "Partida terminada";
-                    return self
-                        .send_message(format!("{}, la palabra era {}", message, self.game.word));
+            }
+            Message::Categories(Err(err)) => {
+                self.show_error(err);
+            }
+            Message::CategorySelected(category) => {
+                self.category = category;
+            }
+            Message::RandomWord => {
+                if let State::NewWord = self.state {
+                    self.state = State::SettingGame;
+                    let category = match &self.category {
+                        CategoryChoice::Any(_) => None,
+                        CategoryChoice::Category(name) => Some(name.clone()),
+                    };
+                    return self.handles.send_random_word(category);
                 }
             }
+            Message::RandomWordSetted(Ok(RandomWord { word, .. })) => {
+                self.start_round(word);
+            }
+            Message::RandomWordSetted(Err(err)) => {
+                self.state = State::NewWord;
+                self.show_error(err);
+            }
             Message::ActualState(Some(game)) => {
                 self.game = game;
+                if self.game.is_completed {
+                    self.finish_game();
+                } else if let State::NewWord | State::GameCompleted = self.state {
+                    // the moderators can start a round from the chat
+                    self.state = State::Playing;
+                }
+            }
+            Message::GameEvent(GameEvent::LeaderboardChanged(leaderboard)) => {
+                self.leaderboard = leaderboard;
             }
-            Message::Close(id) => {
-                self.closing.store(true, Ordering::Relaxed);
-                if let Some(tasks) = &self.tasks {
-                    tasks.iter().for_each(|task| {
-                        if !task.is_finished() {
-                            task.abort();
-                        }
-                    });
+            Message::GameEvent(GameEvent::ConnectionChanged(status)) => {
+                self.connection = Some(status);
+            }
+            Message::GameEvent(GameEvent::Error(err)) => {
+                self.show_error(err);
+            }
+            Message::Reconnect => {
+                if let State::Error = self.state {
+                    self.abort_tasks();
+                    *self = Self {
+                        leaderboard: std::mem::take(&mut self.leaderboard),
+                        control_window: self.control_window,
+                        viewer_window: self.viewer_window,
+                        state: State::Connecting,
+                        category: CategoryChoice::any(self.ui),
+                        ui: self.ui,
+                        view: self.view.clone(),
+                        ..Self::default()
+                    };
+                    return self.connect();
+                }
+            }
+            Message::OpenSettings if self.can_open_settings() => {
+                return TaskCreator::load_settings();
+            }
+            Message::SettingsLoaded(Ok(settings)) if self.can_open_settings() => {
+                self.settings = SettingsForm::new(settings);
+                self.state = State::Settings;
+            }
+            Message::SettingsLoaded(Err(err)) => {
+                self.show_error(err);
+            }
+            Message::SettingsEdited(field) => {
+                self.settings.update(field);
+            }
+            Message::SaveSettings => match self.settings.settings() {
+                Ok(settings) => return self.handles.save_settings(settings),
+                Err(err) => {
+                    let path = CONFIG_FILE.into();
+                    self.show_error(Arc::new(ColgadoLogicError::InvalidConfig { path, err }));
+                }
+            },
+            Message::SettingsSaved(Ok(settings)) => {
+                self.set_ui_language(settings.ui_language());
+                self.error = None;
+                self.close_settings();
+            }
+            Message::SettingsSaved(Err(err)) => {
+                self.show_error(err);
+            }
+            Message::CloseSettings => {
+                self.close_settings();
+            }
+            Message::Close(id) if Some(id) == self.viewer_window => {
+                self.viewer_window = None;
+                return window::close(id);
+            }
+            Message::Close(_) => {
+                if self.handles.is_connected() {
+                    return self.handles.shutdown();
                 }
-                return window::close::<Message>(id);
+                self.abort_tasks();
+                return iced::exit();
+            }
+            Message::ShutDown(result) => {
+                if let Err(err) = result {
+                    colgado_logic::log_error!("{err}");
+                }
+                // the overlay and the mock servers only stop with the tasks
+                self.abort_tasks();
+                return iced::exit();
             }
             _ => {}
         }
         Task::none()
     }
 
-    fn connect(&self) -> Task<Message> {
-        self.handles.connect()
+    fn set_ui_language(&mut self, language: Language) {
+        self.ui = Catalog::new(language);
+        self.view = ColgadoView::new(self.ui);
+        if let CategoryChoice::Any(_) = self.category {
+            self.category = CategoryChoice::any(self.ui);
+        }
+        if let Some(any) = self.categories.first_mut()
+            && let CategoryChoice::Any(_) = any
+        {
+            *any = CategoryChoice::any(self.ui);
+        }
     }
 
-    fn send_new_word(&self) -> Task<Message> {
-        self.handles.send_new_word(&self.game.word)
+    fn can_open_settings(&self) -> bool {
+        matches!(
+            self.state,
+            State::NewConnection | State::NewWord | State::Playing | State::GameCompleted
+        )
     }
 
-    fn send_message(&self, word: String) -> Task<Message> {
-        self.handles.send_message(word)
+    /// Goes back to the screen of the game, which may have changed meanwhile
+    fn close_settings(&mut self) {
+        if let State::Settings = self.state {
+            self.state = if self.tasks.is_none() {
+                State::NewConnection
+            } else if self.game.word.is_empty() {
+                State::NewWord
+            } else if self.game.is_completed {
+                State::GameCompleted
+            } else {
+                State::Playing
+            };
+        }
     }
 
-    #[allow(dead_code)]
-    fn send_messages<T, I>(&self, words: T) -> Task<Message>
-    where
-        T: IntoIterator<Item = String, IntoIter = I> + Send + 'static,
-        I: Send,
-    {
-        self.handles.send_messages(words)
+    /// The game actor announces the round in the chat
+    fn start_round(&mut self, word: String) {
+        self.game.words = word.split_whitespace().map(str::to_owned).collect();
+        self.game.word = word;
+        self.game.is_completed = false;
+        self.secret.clear();
+        self.peek = false;
+        self.state = State::Playing;
+    }
+
+    fn show_error(&mut self, err: Arc<ColgadoLogicError>) {
+        if err.is_fatal() {
+            self.state = State::Error;
+        }
+        self.error = Some(err);
     }
 
-    fn get_game(&self) -> Task<Message> {
-        self.handles.get_game(&self.closing)
+    fn abort_tasks(&self) {
+        if let Some(tasks) = &self.tasks {
+            tasks.iter().for_each(|task| {
+                if !task.is_finished() {
+                    task.abort();
+                }
+            });
+        }
+    }
+
+    fn toggle_viewer(&mut self) -> Task<Message> {
+        match self.viewer_window.take() {
+            Some(id) => window::close(id),
+            None => {
+                let (id, open) = window::open(window_settings());
+                self.viewer_window = Some(id);
+                open.map(|_| Message::None)
+            }
+        }
+    }
+
+    fn connect(&self) -> Task<Message> {
+        self.handles.connect()
+    }
+
+    fn send_new_word(&self) -> Task<Message> {
+        self.handles.send_new_word(&self.secret)
+    }
+
+    fn finish_game(&mut self) {
+        if let State::Playing = self.state {
+            self.state = State::GameCompleted;
+        }
     }
 
-    pub fn view(&self) -> Element<Message> {
-        self.view.view(self)
+    pub fn view(&self, id: window::Id) -> Element<'_, Message> {
+        if Some(id) == self.viewer_window {
+            self.view.viewer_view(self)
+        } else {
+            self.view.view(self)
+        }
     }
 
     pub fn subscription(&self) -> Subscription<Message> {
@@ -206,10 +462,13 @@ impl ColgadoApp {
         let close_event: Subscription<Message> =
             close_requests().map(|id: window::Id| Message::Close(id));
         subscriptions.push(close_event);
-        if let State::Playing = self.state {
-            let game_subscription = iced::time::every(iced::time::Duration::from_millis(10))
-                .map(|_| Message::GetActualState);
-            subscriptions.push(game_subscription);
+        if let Some(game_handle) = self.handles.game_handle() {
+            let game_state = Subscription::run_with_id("game-state", game_states(game_handle))
+                .map(Message::ActualState);
+            let game_events = Subscription::run_with_id("game-events", game_events(game_handle))
+                .map(Message::GameEvent);
+            subscriptions.push(game_state);
+            subscriptions.push(game_events);
         }
         Subscription::batch(subscriptions)
     }
@@ -217,19 +476,57 @@ impl ColgadoApp {
 
 impl Default for ColgadoApp {
     fn default() -> Self {
-        let title = "El que tengo aquí colgado";
+        let ui = Catalog::default();
         Self {
             game: GameView::default(),
+            secret: String::new(),
+            peek: false,
+            categories: Vec::new(),
+            category: CategoryChoice::any(ui),
+            control_window: None,
+            viewer_window: None,
+            leaderboard: Leaderboard::default(),
+            connection: None,
+            error: None,
             state: State::NewConnection,
             tasks: None,
             handles: TaskCreator::default(),
-            closing: Arc::new(AtomicBool::new(false)),
-            command: None,
-            view: ColgadoView::new(title),
+            settings: SettingsForm::default(),
+            ui,
+            view: ColgadoView::new(ui),
         }
     }
 }
 
+/// Stream with every change of the game, ends when the game actor finishes,
+/// which `game_events` reports
+fn game_states(game_handle: &TwitchGameHandle) -> impl Stream<Item = Option<GameView>> + use<> {
+    let receiver = game_handle.watch_game_state();
+    stream::unfold(receiver, |mut receiver| async move {
+        receiver.changed().await.ok()?;
+        let game = receiver.borrow_and_update().clone();
+        Some((game, receiver))
+    })
+}
+
+/// Stream with the events of the game, the end of the game actor is reported as `ActorStopped`
+fn game_events(game_handle: &TwitchGameHandle) -> impl Stream<Item = GameEvent> + use<> {
+    let receiver = game_handle.subscribe_events();
+    stream::unfold(Some(receiver), |receiver| async move {
+        let mut receiver = receiver?;
+        loop {
+            match receiver.recv().await {
+                Ok(event) => return Some((event, Some(receiver))),
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => {
+                    let err = Arc::new(ColgadoLogicError::ActorStopped);
+                    return Some((GameEvent::Error(err), None));
+                }
+            }
+        }
+    })
+}
+
 fn system_theme_mode() -> Theme {
     match dark_light::detect().unwrap_or(dark_light::Mode::Unspecified) {
         dark_light::Mode::Light | dark_light::Mode::Unspecified => Theme::Light,
@@ -239,34 +536,299 @@ fn system_theme_mode() -> Theme {
 
 #[derive(Debug, Clone)]
 pub struct ColgadoView {
-    title: &'static str,
+    ui: Catalog,
 }
 
 impl ColgadoView {
-    pub fn new(title: &'static str) -> Self {
-        ColgadoView { title }
+    pub fn new(ui: Catalog) -> Self {
+        ColgadoView { ui }
     }
 
     pub fn view<'a>(&'a self, colgado_app: &'a ColgadoApp) -> Element<'a, Message> {
         let game = &colgado_app.game;
         let state = &colgado_app.state;
-        let title = self.title;
+        let title = self.ui.text("ui.title");
         let title = text(title).font(TEXT).size(30);
         let title = row![title];
         let mut view = match state {
             State::NewConnection | State::Connecting => self.new_connection_view(state),
-            State::NewWord | State::SettingGame => self.new_word_view(game, state),
-            State::Playing | State::GameCompleted => self.playing_view(game, state),
+            State::NewWord | State::SettingGame => self.new_word_view(colgado_app),
+            State::Playing | State::GameCompleted => {
+                let leaderboard = &colgado_app.leaderboard;
+                let view = self.playing_view(game, state, true);
+                view.push(self.leaderboard_view(leaderboard))
+            }
+            State::Settings => self.settings_view(&colgado_app.settings),
+            State::Error => self.error_view(),
         };
 
         view = view.max_width(600);
-        view = column![title, view].spacing(40).align_x(Center).width(Fill);
+        let status = self.connection_view(colgado_app.connection);
+        let error = self.error_line(colgado_app.error.as_deref());
+        let viewer = self.viewer_button(
+            colgado_app.viewer_window.is_some(),
+            colgado_app.can_open_settings(),
+        );
+        view = column![title, view, status, error, viewer]
+            .spacing(40)
+            .align_x(Center)
+            .width(Fill);
         let view = center(view);
         widget::container(view).into()
     }
 
-    fn new_connection_view(&self, state: &State) -> Column<Message> {
-        let mut button = button(text("Conectar"));
+    /// Public part of the game, the secret word is never shown
+    pub fn viewer_view<'a>(&'a self, colgado_app: &'a ColgadoApp) -> Element<'a, Message> {
+        let game = &colgado_app.game;
+        let state = &colgado_app.state;
+        let title = row![text(self.ui.text("ui.title")).font(TEXT).size(30)];
+        let view = match state {
+            State::Playing | State::GameCompleted => {
+                let leaderboard = &colgado_app.leaderboard;
+                let view = self.playing_view(game, state, false);
+                view.push(self.leaderboard_view(leaderboard))
+            }
+            // the round goes on while the settings are edited
+            State::Settings if !game.word.is_empty() => {
+                let leaderboard = &colgado_app.leaderboard;
+                let view = self.playing_view(game, &State::Playing, false);
+                view.push(self.leaderboard_view(leaderboard))
+            }
+            _ => column![text(self.ui.text("ui.preparing-word")).size(20)]
+                .width(Fill)
+                .align_x(Center),
+        };
+        let view = column![title, view.max_width(600)]
+            .spacing(40)
+            .align_x(Center)
+            .width(Fill);
+        widget::container(center(view)).into()
+    }
+
+    fn viewer_button<'a>(&self, is_open: bool, settings: bool) -> Column<'a, Message> {
+        let label = if is_open {
+            self.ui.text("ui.close-viewer")
+        } else {
+            self.ui.text("ui.open-viewer")
+        };
+        let viewer = button(text(label).size(14)).on_press(Message::ToggleViewer);
+        let mut settings_button = button(text(self.ui.text("ui.settings")).size(14));
+        if settings {
+            settings_button = settings_button.on_press(Message::OpenSettings);
+        }
+        let buttons = row![viewer, settings_button].spacing(10);
+        column![buttons].width(Fill).align_x(Center)
+    }
+
+    fn settings_view<'a>(&self, form: &'a SettingsForm) -> Column<'a, Message> {
+        let field = |label: &'static str, value: &'a str, edit: fn(String) -> SettingsField| {
+            let input =
+                text_input("", value).on_input(move |value| Message::SettingsEdited(edit(value)));
+            column![text(self.ui.text(label)).size(14), input].spacing(5)
+        };
+        let toggle = |label: &'static str, value: bool, edit: fn(bool) -> SettingsField| {
+            checkbox(self.ui.text(label), value)
+                .on_toggle(move |value| Message::SettingsEdited(edit(value)))
+        };
+        let language = |label: &'static str, value, edit: fn(Language) -> SettingsField| {
+            let languages = pick_list(Language::ALL, Some(value), move |language| {
+                Message::SettingsEdited(edit(language))
+            });
+            column![text(self.ui.text(label)).size(14), languages].spacing(5)
+        };
+        let announce = |label: &'static str, announcement| {
+            checkbox(self.ui.text(label), form.announce.is_enabled(announcement)).on_toggle(
+                move |value| Message::SettingsEdited(SettingsField::Announce(announcement, value)),
+            )
+        };
+        let heading = |label: &'static str| text(self.ui.text(label)).size(20);
+
+        let chat = column![
+            field("ui.setting-command", &form.command, SettingsField::Command),
+            field(
+                "ui.setting-client-id",
+                &form.client_id,
+                SettingsField::ClientId
+            ),
+            language(
+                "ui.setting-ui-language",
+                form.ui_language,
+                SettingsField::UiLanguage
+            ),
+            language(
+                "ui.setting-chat-language",
+                form.chat_language,
+                SettingsField::ChatLanguage
+            ),
+        ]
+        .spacing(10);
+        let rules = column![
+            heading("ui.settings-game"),
+            field("ui.setting-lives", &form.lives, SettingsField::Lives),
+            field(
+                "ui.setting-solve-penalty",
+                &form.solve_penalty,
+                SettingsField::SolvePenalty
+            ),
+            field(
+                "ui.setting-max-word-length",
+                &form.max_word_length,
+                SettingsField::MaxWordLength
+            ),
+            toggle(
+                "ui.setting-fold-diacritics",
+                form.fold_diacritics,
+                SettingsField::FoldDiacritics
+            ),
+            toggle(
+                "ui.setting-keep-enie",
+                form.keep_enie,
+                SettingsField::KeepEnie
+            ),
+        ]
+        .spacing(10);
+        let scoring = column![
+            heading("ui.settings-scoring"),
+            field(
+                "ui.setting-letter-points",
+                &form.letter_points,
+                SettingsField::LetterPoints
+            ),
+            field(
+                "ui.setting-solve-points",
+                &form.solve_points,
+                SettingsField::SolvePoints
+            ),
+            field(
+                "ui.setting-wrong-solve-points",
+                &form.wrong_solve_points,
+                SettingsField::WrongSolvePoints
+            ),
+        ]
+        .spacing(10);
+        let announcements = column![
+            heading("ui.settings-announce"),
+            announce("ui.setting-announce-round-start", Announcement::RoundStart),
+            announce(
+                "ui.setting-announce-letter-found",
+                Announcement::LetterFound
+            ),
+            announce("ui.setting-announce-wrong-guess", Announcement::WrongGuess),
+            announce("ui.setting-announce-win", Announcement::Win),
+            announce("ui.setting-announce-loss", Announcement::Loss),
+            announce("ui.setting-announce-timeout", Announcement::TimeOut),
+        ]
+        .spacing(10);
+        let timer = column![
+            heading("ui.settings-timer"),
+            toggle("ui.setting-timer", form.timer, SettingsField::Timer),
+            field(
+                "ui.setting-round-seconds",
+                &form.round_seconds,
+                SettingsField::RoundSeconds
+            ),
+            field(
+                "ui.setting-hint-seconds",
+                &form.hint_seconds,
+                SettingsField::HintSeconds
+            ),
+            field(
+                "ui.setting-timer-warnings",
+                &form.timer_warnings,
+                SettingsField::TimerWarnings
+            ),
+        ]
+        .spacing(10);
+        let limits = column![
+            heading("ui.settings-limits"),
+            toggle("ui.setting-limits", form.limits, SettingsField::Limits),
+            field(
+                "ui.setting-player-cooldown",
+                &form.player_cooldown_ms,
+                SettingsField::PlayerCooldown
+            ),
+            field(
+                "ui.setting-max-guesses-per-round",
+                &form.max_guesses_per_round,
+                SettingsField::MaxGuessesPerRound
+            ),
+            field(
+                "ui.setting-global-max-guesses",
+                &form.global_max_guesses,
+                SettingsField::GlobalMaxGuesses
+            ),
+            field(
+                "ui.setting-global-window",
+                &form.global_window_ms,
+                SettingsField::GlobalWindow
+            ),
+            toggle(
+                "ui.setting-limit-replies",
+                form.limit_replies,
+                SettingsField::LimitReplies
+            ),
+        ]
+        .spacing(10);
+        let overlay = column![
+            heading("ui.settings-overlay"),
+            toggle("ui.setting-overlay", form.overlay, SettingsField::Overlay),
+            field(
+                "ui.setting-overlay-port",
+                &form.overlay_port,
+                SettingsField::OverlayPort
+            ),
+            field(
+                "ui.setting-leaderboard-size",
+                &form.leaderboard_size,
+                SettingsField::LeaderboardSize
+            ),
+        ]
+        .spacing(10);
+        let form = column![chat, rules, scoring, announcements, timer, limits, overlay]
+            .spacing(30)
+            .padding(10);
+
+        let note = text(self.ui.text("ui.settings-next-start")).size(14);
+        let save = button(text(self.ui.text("ui.save"))).on_press(Message::SaveSettings);
+        let cancel = button(text(self.ui.text("ui.cancel"))).on_press(Message::CloseSettings);
+        column![
+            scrollable(form).height(400),
+            note,
+            row![cancel, save].spacing(10)
+        ]
+        .spacing(20)
+        .width(Fill)
+        .align_x(Center)
+    }
+
+    fn connection_view<'a>(&self, connection: Option<ConnectionStatus>) -> Column<'a, Message> {
+        let status = match connection {
+            Some(ConnectionStatus::Connected) => self.ui.text("ui.connected"),
+            Some(ConnectionStatus::Reconnecting) => self.ui.text("ui.reconnecting"),
+            Some(ConnectionStatus::Disconnected) => self.ui.text("ui.disconnected"),
+            None => "",
+        };
+        column![text(status).size(14)].width(Fill).align_x(Center)
+    }
+
+    fn error_line<'a>(&self, error: Option<&ColgadoLogicError>) -> Column<'a, Message> {
+        let error = error
+            .map(|err| self.ui.format("ui.error", &[("error", err)]))
+            .unwrap_or_default();
+        column![text(error).size(14)].width(Fill).align_x(Center)
+    }
+
+    fn error_view<'a>(&self) -> Column<'a, Message> {
+        let message = text(self.ui.text("ui.connection-lost")).size(20);
+        let button = button(text(self.ui.text("ui.reconnect"))).on_press(Message::Reconnect);
+        column![message, button]
+            .spacing(10)
+            .width(Fill)
+            .align_x(Center)
+    }
+
+    fn new_connection_view(&self, state: &State) -> Column<'_, Message> {
+        let mut button = button(text(self.ui.text("ui.connect")));
         button = if let State::NewConnection = state {
             button.on_press(Message::NewConnection)
         } else {
@@ -275,32 +837,105 @@ impl ColgadoView {
         column![button].width(Fill).align_x(Center)
     }
 
-    fn new_word_view(&self, game: &GameView, state: &State) -> Column<Message> {
-        let mut send_button = button(text("Jugar"));
-        send_button = if let State::NewWord = state {
-            send_button.on_press(Message::SubmitWord)
+    fn new_word_view<'a>(&self, colgado_app: &'a ColgadoApp) -> Column<'a, Message> {
+        let peek = colgado_app.peek;
+        let mut send_button = button(text(self.ui.text("ui.play")));
+        let mut random_button = button(text(self.ui.text("ui.random-word")));
+        let placeholder = self.ui.text("ui.word-placeholder");
+        let mut input = text_input(placeholder, &colgado_app.secret).secure(!peek);
+        if let State::NewWord = colgado_app.state {
+            send_button = send_button.on_press(Message::SubmitWord);
+            random_button = random_button.on_press(Message::RandomWord);
+            input = input
+                .on_input(Message::NewWord)
+                .on_submit(Message::SubmitWord);
+        }
+        let peek_label = if peek {
+            self.ui.text("ui.hide")
         } else {
-            send_button
+            self.ui.text("ui.show")
         };
+        let peek_button = button(text(peek_label)).on_press(Message::TogglePeek);
         let word_input = column![
-            text("Introduce una palabra:"),
-            row![
-                text_input("Palabra", &game.word).on_input(Message::NewWord),
-                send_button,
-            ]
+            text(self.ui.text("ui.enter-word")),
+            row![input, peek_button, send_button]
         ];
-        column![word_input].width(Fill).align_x(Center)
+        let mut column = column![word_input].spacing(20);
+        if !colgado_app.categories.is_empty() {
+            let categories = pick_list(
+                colgado_app.categories.as_slice(),
+                Some(&colgado_app.category),
+                Message::CategorySelected,
+            );
+            column = column.push(column![
+                text(self.ui.text("ui.pick-random")),
+                row![categories, random_button].spacing(10)
+            ]);
+        }
+        column.width(Fill).align_x(Center)
     }
 
-    fn playing_view<'a>(&'a self, game: &'a GameView, state: &State) -> Column<'a, Message> {
-        let text_input = text(&game.word);
-        let word_input = column![text_input.size(40)];
+    fn playing_view<'a>(
+        &'a self,
+        game: &'a GameView,
+        state: &State,
+        controls: bool,
+    ) -> Column<'a, Message> {
+        let hangman = canvas(Hangman::new(game)).width(200).height(200);
+        let words = game.words.iter().map(|word| text(word).size(40).into());
+        let word_input = column![row(words).spacing(25).wrap()];
         let letters = column![text(&game.letters).size(40)];
-        let mut column = column![word_input, letters].spacing(10);
+        let lives = self.ui.format(
+            "ui.lives",
+            &[("lives", &game.lives), ("max-lives", &game.max_lives)],
+        );
+        let lives = text(lives).size(20);
+        let mut column = column![hangman, word_input, letters, lives]
+            .spacing(10)
+            .align_x(Center);
+        if let Some(seconds) = game.remaining_seconds {
+            let time = format!("{}:{:02}", seconds / 60, seconds % 60);
+            let time = self.ui.format("ui.time", &[("time", &time)]);
+            column = column.push(text(time).size(20));
+        }
 
         if let State::GameCompleted = state {
-            let button = button(text("Nueva partida")).on_press(Message::NewGame);
-            column = column.push(button);
+            let result = match game.outcome {
+                GameOutcome::Won => self.ui.text("ui.won"),
+                GameOutcome::Lost if game.timed_out => self.ui.text("ui.timed-out"),
+                GameOutcome::Lost if game.ended => self.ui.text("ui.ended"),
+                GameOutcome::Lost => self.ui.text("ui.lost"),
+                GameOutcome::InProgress => "",
+            };
+            column = column.push(text(result).size(20));
+            if let (GameOutcome::Lost, Some(solution)) = (game.outcome, &game.solution) {
+                let solution = self.ui.format("ui.solution", &[("word", solution)]);
+                column = column.push(text(solution).size(20));
+            }
+            if controls {
+                let button = button(text(self.ui.text("ui.new-game"))).on_press(Message::NewGame);
+                column = column.push(button);
+            }
+        }
+        column.width(Fill).align_x(Center)
+    }
+
+    fn leaderboard_view<'a>(&'a self, leaderboard: &'a Leaderboard) -> Column<'a, Message> {
+        let mut column = column![text(self.ui.text("ui.leaderboard")).size(20)].spacing(5);
+        if leaderboard.is_empty() {
+            column = column.push(text(self.ui.text("ui.leaderboard-empty")));
+        }
+        for (i, player) in leaderboard.iter().take(10).enumerate() {
+            let line = self.ui.format(
+                "ui.leaderboard-line",
+                &[
+                    ("position", &(i + 1)),
+                    ("player", &player.player_name),
+                    ("points", &player.points),
+                    ("wins", &player.wins),
+                ],
+            );
+            column = column.push(text(line));
         }
         column.width(Fill).align_x(Center)
     }
@@ -328,6 +963,36 @@ impl TaskCreator {
             .perform()
     }
 
+    pub fn load_settings() -> Task<Message> {
+        let closure = async {
+            Config::load(CONFIG_FILE)
+                .await
+                .map(|config| config.settings())
+        };
+        TaskBuider::default()
+            .set_closure(closure)
+            .set_mapping(Message::SettingsLoaded)
+            .err_to_arc()
+            .perform()
+    }
+
+    /// Saves the settings and applies them to the game when connected
+    pub fn save_settings(&self, settings: Settings) -> Task<Message> {
+        let game_handle = self.game_handle().cloned();
+        let closure = async move {
+            settings.save(CONFIG_FILE).await?;
+            if let Some(game_handle) = game_handle {
+                game_handle.apply_settings(settings.clone()).await?;
+            }
+            Ok(settings)
+        };
+        TaskBuider::default()
+            .set_closure(closure)
+            .set_mapping(Message::SettingsSaved)
+            .err_to_arc()
+            .perform()
+    }
+
     pub fn send_new_word(&self, word: &str) -> Task<Message> {
         let handle_closure = |game_handle: TwitchGameHandle| {
             let word = word.to_owned();
@@ -335,43 +1000,51 @@ impl TaskCreator {
         };
         let mapping = Message::WordSetted;
 
-        self.create_game_task(handle_closure, mapping).perform()
+        self.create_game_task(handle_closure, mapping)
+            .err_to_arc()
+            .perform()
     }
 
-    pub fn send_message(&self, word: String) -> Task<Message> {
-        let handle_closure =
-            |game_handle: TwitchGameHandle| async move { game_handle.send_message(word).await };
-        let mapping = |_| Message::GetActualState;
+    pub fn send_random_word(&self, category: Option<String>) -> Task<Message> {
+        let handle_closure = |game_handle: TwitchGameHandle| async move {
+            game_handle.set_random_word(category).await
+        };
+        let mapping = Message::RandomWordSetted;
 
-        self.create_game_task(handle_closure, mapping).perform()
+        self.create_game_task(handle_closure, mapping)
+            .err_to_arc()
+            .perform()
     }
 
-    pub fn send_messages<T, I>(&self, words: T) -> Task<Message>
-    where
-        T: IntoIterator<Item = String, IntoIter = I> + Send + 'static,
-        I: Send,
-    {
+    pub fn get_categories(&self) -> Task<Message> {
         let handle_closure =
-            |game_handle: TwitchGameHandle| async move { game_handle.send_messages(words).await };
-        let mapping = |_| Message::GetActualState;
+            |game_handle: TwitchGameHandle| async move { game_handle.get_categories().await };
+        let mapping = Message::Categories;
 
-        self.create_game_task(handle_closure, mapping).perform()
+        self.create_game_task(handle_closure, mapping)
+            .err_to_arc()
+            .perform()
     }
 
-    pub fn get_game(&self, closing: &Arc<AtomicBool>) -> Task<Message> {
-        let handle_closure = |game_handle: TwitchGameHandle| {
-            let closing = closing.clone();
-            async move {
-                if !closing.load(Ordering::Relaxed) {
-                    game_handle.get_game_state().await
-                } else {
-                    None
-                }
-            }
+    /// Lets the actors finish their work and close the connection
+    pub fn shutdown(&self) -> Task<Message> {
+        let handle_closure = |handles: &Handles| {
+            let handles = handles.clone();
+            async move { handles.shutdown().await }
         };
-        let mapping = Message::ActualState;
+        let mapping = Message::ShutDown;
+
+        self.create_task(handle_closure, mapping)
+            .err_to_arc()
+            .perform()
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.handles.is_some()
+    }
 
-        self.create_game_task(handle_closure, mapping).perform()
+    pub fn game_handle(&self) -> Option<&TwitchGameHandle> {
+        self.handles.as_ref().map(|handles| &handles.game_handle)
     }
 
     fn create_task<T, E, F, M>(
@@ -0,0 +1,235 @@
+use std::str::FromStr;
+
+use colgado_logic::{
+    announcements::{Announcement, AnnouncementToggles},
+    errors::InvalidValue,
+    i18n::Language,
+    limits::GuessLimitsConfig,
+    overlay::OverlayConfig,
+    settings::Settings,
+    timer::TimerConfig,
+};
+
+/// Change made to a field of the settings screen
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SettingsField {
+    Command(String),
+    ClientId(String),
+    UiLanguage(Language),
+    ChatLanguage(Language),
+    Lives(String),
+    SolvePenalty(String),
+    MaxWordLength(String),
+    LetterPoints(String),
+    SolvePoints(String),
+    WrongSolvePoints(String),
+    FoldDiacritics(bool),
+    KeepEnie(bool),
+    Announce(Announcement, bool),
+    Timer(bool),
+    RoundSeconds(String),
+    HintSeconds(String),
+    TimerWarnings(String),
+    Limits(bool),
+    PlayerCooldown(String),
+    MaxGuessesPerRound(String),
+    GlobalMaxGuesses(String),
+    GlobalWindow(String),
+    LimitReplies(bool),
+    Overlay(bool),
+    OverlayPort(String),
+    LeaderboardSize(String),
+}
+
+/// Values of the settings screen, the numbers are kept as typed until they are saved
+#[derive(Clone, Debug, Default)]
+pub struct SettingsForm {
+    /// Settings read from the file, what the form does not show is saved as it was
+    settings: Settings,
+    pub command: String,
+    pub client_id: String,
+    pub ui_language: Language,
+    pub chat_language: Language,
+    pub lives: String,
+    pub solve_penalty: String,
+    /// Empty for no limit
+    pub max_word_length: String,
+    pub letter_points: String,
+    pub solve_points: String,
+    pub wrong_solve_points: String,
+    pub fold_diacritics: bool,
+    pub keep_enie: bool,
+    pub announce: AnnouncementToggles,
+    pub timer: bool,
+    pub round_seconds: String,
+    pub hint_seconds: String,
+    /// Seconds separated by commas
+    pub timer_warnings: String,
+    pub limits: bool,
+    pub player_cooldown_ms: String,
+    /// Empty for no limit
+    pub max_guesses_per_round: String,
+    /// Empty for no limit
+    pub global_max_guesses: String,
+    pub global_window_ms: String,
+    pub limit_replies: bool,
+    pub overlay: bool,
+    pub overlay_port: String,
+    pub leaderboard_size: String,
+}
+
+impl SettingsForm {
+    pub fn new(settings: Settings) -> Self {
+        let game = &settings.game;
+        let timer = game.timer.clone().unwrap_or_default();
+        let limits = game.limits.clone().unwrap_or_default();
+        let overlay = settings.overlay.clone().unwrap_or_default();
+        Self {
+            command: settings.command.clone(),
+            client_id: settings.client_id.clone(),
+            ui_language: settings.ui_language(),
+            chat_language: settings.chat_language(),
+            lives: game.lives.to_string(),
+            solve_penalty: game.solve_penalty.to_string(),
+            max_word_length: optional_text(game.max_word_length),
+            letter_points: game.scoring.letter.to_string(),
+            solve_points: game.scoring.solve.to_string(),
+            wrong_solve_points: game.scoring.wrong_solve.to_string(),
+            fold_diacritics: game.normalization.fold_diacritics,
+            keep_enie: game.normalization.keep_enie,
+            announce: game.announce,
+            timer: game.enabled_timer().is_some(),
+            round_seconds: timer.round_seconds.to_string(),
+            hint_seconds: timer.hint_seconds.to_string(),
+            timer_warnings: timer
+                .warnings
+                .iter()
+                .map(u64::to_string)
+                .collect::<Vec<_>>()
+                .join(", "),
+            limits: game.enabled_limits().is_some(),
+            player_cooldown_ms: limits.player_cooldown_ms.to_string(),
+            max_guesses_per_round: optional_text(limits.max_guesses_per_round),
+            global_max_guesses: optional_text(limits.global_max_guesses),
+            global_window_ms: limits.global_window_ms.to_string(),
+            limit_replies: limits.replies,
+            overlay: settings.overlay.is_some() && overlay.enabled,
+            overlay_port: overlay.port.to_string(),
+            leaderboard_size: overlay.leaderboard_size.to_string(),
+            settings,
+        }
+    }
+
+    pub fn update(&mut self, field: SettingsField) {
+        match field {
+            SettingsField::Command(command) => self.command = command,
+            SettingsField::ClientId(client_id) => self.client_id = client_id,
+            SettingsField::UiLanguage(language) => self.ui_language = language,
+            SettingsField::ChatLanguage(language) => self.chat_language = language,
+            SettingsField::Lives(lives) => self.lives = lives,
+            SettingsField::SolvePenalty(penalty) => self.solve_penalty = penalty,
+            SettingsField::MaxWordLength(max) => self.max_word_length = max,
+            SettingsField::LetterPoints(points) => self.letter_points = points,
+            SettingsField::SolvePoints(points) => self.solve_points = points,
+            SettingsField::WrongSolvePoints(points) => self.wrong_solve_points = points,
+            SettingsField::FoldDiacritics(fold) => self.fold_diacritics = fold,
+            SettingsField::KeepEnie(keep) => self.keep_enie = keep,
+            SettingsField::Announce(announcement, enabled) => {
+                self.announce.set_enabled(announcement, enabled);
+            }
+            SettingsField::Timer(enabled) => self.timer = enabled,
+            SettingsField::RoundSeconds(seconds) => self.round_seconds = seconds,
+            SettingsField::HintSeconds(seconds) => self.hint_seconds = seconds,
+            SettingsField::TimerWarnings(warnings) => self.timer_warnings = warnings,
+            SettingsField::Limits(enabled) => self.limits = enabled,
+            SettingsField::PlayerCooldown(millis) => self.player_cooldown_ms = millis,
+            SettingsField::MaxGuessesPerRound(max) => self.max_guesses_per_round = max,
+            SettingsField::GlobalMaxGuesses(max) => self.global_max_guesses = max,
+            SettingsField::GlobalWindow(millis) => self.global_window_ms = millis,
+            SettingsField::LimitReplies(replies) => self.limit_replies = replies,
+            SettingsField::Overlay(enabled) => self.overlay = enabled,
+            SettingsField::OverlayPort(port) => self.overlay_port = port,
+            SettingsField::LeaderboardSize(size) => self.leaderboard_size = size,
+        }
+    }
+
+    /// Settings with the values of the form, the first invalid one is returned
+    pub fn settings(&self) -> Result<Settings, InvalidValue> {
+        let mut settings = self.settings.clone();
+        settings.command = self.command.trim().to_owned();
+        settings.client_id = self.client_id.trim().to_owned();
+        // the languages are only saved when they differ from the common one
+        settings.ui_language = (self.ui_language != settings.language).then_some(self.ui_language);
+        settings.chat_language =
+            (self.chat_language != settings.language).then_some(self.chat_language);
+
+        let game = &mut settings.game;
+        game.lives = parse(&self.lives, "game.lives")?;
+        game.solve_penalty = parse(&self.solve_penalty, "game.solve-penalty")?;
+        game.max_word_length = parse_optional(&self.max_word_length, "game.max-word-length")?;
+        game.scoring.letter = parse(&self.letter_points, "game.scoring.letter")?;
+        game.scoring.solve = parse(&self.solve_points, "game.scoring.solve")?;
+        game.scoring.wrong_solve = parse(&self.wrong_solve_points, "game.scoring.wrong-solve")?;
+        game.normalization.fold_diacritics = self.fold_diacritics;
+        game.normalization.keep_enie = self.keep_enie;
+        game.announce = self.announce;
+
+        // like the overlay, a missing section stays missing while it is off
+        if self.timer || game.timer.is_some() {
+            let timer = game.timer.get_or_insert_with(TimerConfig::default);
+            timer.enabled = self.timer;
+            timer.round_seconds = parse(&self.round_seconds, "game.timer.round-seconds")?;
+            timer.hint_seconds = parse(&self.hint_seconds, "game.timer.hint-seconds")?;
+            timer.warnings = self
+                .timer_warnings
+                .split(',')
+                .filter(|seconds| !seconds.trim().is_empty())
+                .map(|seconds| parse(seconds, "game.timer.warnings"))
+                .collect::<Result<_, _>>()?;
+        }
+        if self.limits || game.limits.is_some() {
+            let limits = game.limits.get_or_insert_with(GuessLimitsConfig::default);
+            limits.enabled = self.limits;
+            limits.player_cooldown_ms =
+                parse(&self.player_cooldown_ms, "game.limits.player-cooldown-ms")?;
+            limits.max_guesses_per_round = parse_optional(
+                &self.max_guesses_per_round,
+                "game.limits.max-guesses-per-round",
+            )?;
+            limits.global_max_guesses =
+                parse_optional(&self.global_max_guesses, "game.limits.global-max-guesses")?;
+            limits.global_window_ms =
+                parse(&self.global_window_ms, "game.limits.global-window-ms")?;
+            limits.replies = self.limit_replies;
+        }
+
+        // a missing section stays missing while the overlay is off
+        if self.overlay || settings.overlay.is_some() {
+            let overlay = settings.overlay.get_or_insert_with(OverlayConfig::default);
+            overlay.enabled = self.overlay;
+            overlay.port = parse(&self.overlay_port, "overlay.port")?;
+            overlay.leaderboard_size = parse(&self.leaderboard_size, "overlay.leaderboard-size")?;
+        }
+
+        settings.validate()?;
+        Ok(settings)
+    }
+}
+
+fn parse<T: FromStr>(text: &str, key: &'static str) -> Result<T, InvalidValue> {
+    text.trim()
+        .parse()
+        .map_err(|_| InvalidValue::new(key, "must be a number"))
+}
+
+/// An empty text is no value
+fn parse_optional<T: FromStr>(text: &str, key: &'static str) -> Result<Option<T>, InvalidValue> {
+    match text.trim() {
+        "" => Ok(None),
+        text => parse(text, key).map(Some),
+    }
+}
+
+fn optional_text(value: Option<usize>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
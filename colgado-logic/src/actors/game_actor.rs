@@ -1,89 +1,230 @@
-use crate::word::Game;
-use std::cell::OnceCell;
+use std::{sync::Arc, time::Duration};
 
-use tokio::sync::{mpsc, oneshot};
-use trequests::models::info;
-use trequests::models::requests::send_msg_request::SendMsgRequest;
+use crate::announcements::{Announcement, Announcer, Placeholders};
+use crate::chat_commands::ChatCommand;
+use crate::errors::ColgadoLogicError;
+use crate::game_config::GameConfig;
+use crate::i18n::Catalog;
+use crate::limits::GuessLimiter;
+use crate::retry::RetryPolicy;
+use crate::score::Scoreboard;
+use crate::settings::Settings;
+use crate::stats::StatsStore;
+use crate::timer::{RoundTimer, TimerEvent};
+use crate::word::{Game, GameOutcome, GuessResult};
+use crate::word_bank::{RandomWord, WordBank};
+use tokio::sync::{broadcast, mpsc, oneshot, watch};
+use tokio::time::{Instant, MissedTickBehavior};
 
+use super::models::events::GameEvent;
 use super::models::game_view::GameView;
+use super::models::leaderboard::Leaderboard;
 use super::models::messages::{CommandMessage, GeneralMessage, TwitchMessage};
+use super::transport::{ChatSink, ChatTransport};
 
-pub struct TwitchGameActor {
+const EVENTS_CAPACITY: usize = 100;
+/// How often the round timer is checked and the remaining time published
+const TIMER_TICK: Duration = Duration::from_secs(1);
+
+/// Everything the game needs besides the chat, `init_flow` reads it from `env.toml`
+#[derive(Debug, Default)]
+pub struct GameSetup {
+    /// Prefix of the whole-word guesses and of the subcommands, like `!colgado`
+    pub command: String,
+    pub stats: StatsStore,
+    pub words: WordBank,
+    pub rules: GameConfig,
+    pub announcer: Announcer,
+}
+
+pub struct TwitchGameActor<Transport> {
     receiver: mpsc::UnboundedReceiver<GeneralMessage>,
-    session_id: OnceCell<String>,
+    state_sender: watch::Sender<Option<GameView>>,
+    events_sender: broadcast::Sender<GameEvent>,
+    session_id: Option<String>,
     game: Option<Game>,
-    user_info: info::User,
-    bot_info: info::Bot,
+    rules: GameConfig,
+    /// Category of the round when the word is random
+    category: Option<String>,
+    scoreboard: Scoreboard,
+    stats: StatsStore,
+    words: WordBank,
+    /// Timer of the round in progress
+    timer: Option<RoundTimer>,
+    limiter: Option<GuessLimiter>,
+    /// Texts of the messages sent to the chat
+    catalog: Catalog,
+    announcer: Announcer,
+    chat: Transport,
     command: String,
+    retry_policy: RetryPolicy,
+    /// Answered once the actor has stopped, after a shutdown command
+    shutdown: Option<oneshot::Sender<()>>,
 }
 
-impl TwitchGameActor {
+impl<Transport: ChatTransport> TwitchGameActor<Transport> {
     pub fn new(
         receiver: mpsc::UnboundedReceiver<GeneralMessage>,
-        user_info: info::User,
-        bot_info: info::Bot,
+        state_sender: watch::Sender<Option<GameView>>,
+        events_sender: broadcast::Sender<GameEvent>,
+        chat: Transport,
         command: String,
+        stats: StatsStore,
+        words: WordBank,
     ) -> Self {
-        let session_id = OnceCell::<String>::new();
         let command = command + " ";
         Self {
             receiver,
-            session_id,
+            state_sender,
+            events_sender,
+            session_id: None,
             game: None,
-            user_info,
-            bot_info,
+            rules: GameConfig::default(),
+            category: None,
+            scoreboard: Scoreboard::default(),
+            stats,
+            words,
+            timer: None,
+            limiter: None,
+            catalog: Catalog::default(),
+            announcer: Announcer::default(),
+            chat,
             command,
+            retry_policy: RetryPolicy::default(),
+            shutdown: None,
         }
     }
 
+    fn from_setup(
+        receiver: mpsc::UnboundedReceiver<GeneralMessage>,
+        state_sender: watch::Sender<Option<GameView>>,
+        events_sender: broadcast::Sender<GameEvent>,
+        chat: Transport,
+        setup: GameSetup,
+    ) -> Self {
+        let GameSetup {
+            command,
+            stats,
+            words,
+            rules,
+            announcer,
+        } = setup;
+        Self::new(
+            receiver,
+            state_sender,
+            events_sender,
+            chat,
+            command,
+            stats,
+            words,
+        )
+        .with_rules(rules)
+        .with_announcer(announcer)
+    }
+
+    /// Sets the rules of the rounds started from now on, the scoring and the guess limits
+    pub fn with_rules(mut self, rules: GameConfig) -> Self {
+        self.scoreboard = Scoreboard::new(rules.scoring);
+        self.limiter = rules.enabled_limits().cloned().map(GuessLimiter::new);
+        self.rules = rules;
+        self
+    }
+
+    /// Sets the announcements of the rounds and the language of the other messages
+    pub fn with_announcer(mut self, announcer: Announcer) -> Self {
+        self.catalog = announcer.catalog();
+        self.announcer = announcer;
+        self
+    }
+
     async fn handle(&mut self, message: GeneralMessage) -> bool {
         match message {
-            GeneralMessage::CommandMessage(command) => self.handle_command_message(command),
+            GeneralMessage::CommandMessage(command) => self.handle_command_message(command).await,
             GeneralMessage::TwitchMessage(message) => self.handle_twitch_message(message).await,
+            GeneralMessage::ConnectionStatus(status) => {
+                self.publish_event(GameEvent::ConnectionChanged(status));
+                true
+            }
             GeneralMessage::TwitchSendMessage(message) => {
-                let user_id = &self.user_info.user_id;
-                SendMsgRequest::new(user_id, user_id, &message)
-                    .bot_name("Colgado")
-                    .send(&self.bot_info)
-                    .await
-                    .expect("Error sending message: {message}");
-
+                self.send_chat_message(&message, None).await;
+                true
+            }
+            GeneralMessage::Error(err) => {
+                self.publish_error(err);
                 true
             }
         }
     }
 
     async fn handle_twitch_message(&mut self, message: TwitchMessage) -> bool {
-        let user_id = &self.user_info.user_id;
         match message {
-            TwitchMessage::WelcomeMessage { session_id } => {
-                trequests::subscribe_to_wb(&self.bot_info, &session_id, user_id, user_id)
-                    .await
-                    .expect("Error suscribing to channel");
-                self.session_id.set(session_id).expect("Error setting id");
+            TwitchMessage::WelcomeMessage { session_id, .. } => {
+                let chat = &self.chat;
+                let session = &session_id;
+                let result = self
+                    .retry_policy
+                    .run("Subscription", move || chat.subscribe(session))
+                    .await;
+                if let Err(err) = result {
+                    self.publish_error(ColgadoLogicError::SubscriptionError { err });
+                }
+                self.session_id = Some(session_id);
+            }
+            TwitchMessage::SessionReconnected { session_id } => {
+                self.session_id = Some(session_id);
             }
             TwitchMessage::PlayerMessage {
                 message_text,
                 message_id,
-                ..
+                player_id,
+                player_name,
+                role,
             } => {
-                let word_chars = if let Some(game) = &self.game
-                    && !game.is_completed()
+                // without permission the subcommand is a guess like any other message
+                if let Some(command) = self.chat_command(&message_text)
+                    && role >= command.required_role()
+                {
+                    self.handle_chat_command(command, &message_id).await;
+                    return true;
+                }
+                let mut word_chars = if let Some(game) = &self.game
+                    && game.outcome() == GameOutcome::InProgress
                 {
                     self.valid_player_message(&message_text)
                 } else {
                     None
                 };
+                if word_chars.is_some()
+                    && let Some(limiter) = &mut self.limiter
+                    && let Err(rejection) = limiter.check(&player_id, Instant::now())
+                {
+                    word_chars = None;
+                    if limiter.should_reply(&player_id, rejection) {
+                        let reply = rejection.twitch_message(self.catalog);
+                        self.send_chat_message(&reply, Some(&message_id)).await;
+                    }
+                }
                 if let (Some(word_chars), Some(game)) = (word_chars, &mut self.game) {
-                    let result = game.check_word_chars(&word_chars);
-                    if let Err(err) = result {
-                        let game_error = err.twitch_message_error();
-                        SendMsgRequest::new(user_id, user_id, game_error)
-                            .reply_to(&message_id)
-                            .bot_name("Colgado")
-                            .send(&self.bot_info)
-                            .await
-                            .expect("Error sending message to player");
+                    match game.guess(&word_chars) {
+                        Ok(result) => {
+                            let outcome = game.outcome();
+                            let reply = match result {
+                                GuessResult::LetterFound(_) => Some(Announcement::LetterFound),
+                                GuessResult::WrongSolve => Some(Announcement::WrongGuess),
+                                _ => None,
+                            };
+                            if let Some(reply) = reply {
+                                self.announce(reply, Some(&player_name), Some(&message_id))
+                                    .await;
+                            }
+                            self.record_guess(&player_id, &player_name, result, outcome)
+                                .await;
+                            self.publish_state();
+                        }
+                        Err(err) => {
+                            let reply = err.twitch_message_error(self.catalog);
+                            self.send_chat_message(reply, Some(&message_id)).await;
+                        }
                     }
                 }
             }
@@ -93,6 +234,248 @@ impl TwitchGameActor {
         true
     }
 
+    /// Subcommand of the game command, in the language of the chat
+    fn chat_command(&self, message_text: &str) -> Option<ChatCommand> {
+        let text = message_text.strip_prefix(&self.command)?;
+        match ChatCommand::parse(text, self.catalog)? {
+            // anything but a category after the start subcommand is a guess
+            ChatCommand::Start(Some(category)) => {
+                let category = self.words.category(&category)?;
+                Some(ChatCommand::Start(Some(category.to_owned())))
+            }
+            command => Some(command),
+        }
+    }
+
+    async fn handle_chat_command(&mut self, command: ChatCommand, message_id: &str) {
+        let in_progress = self
+            .game
+            .as_ref()
+            .is_some_and(|game| game.outcome() == GameOutcome::InProgress);
+        match command {
+            ChatCommand::Start(_) if in_progress => {
+                let message = self.catalog.text("chat.round-in-progress");
+                self.send_chat_message(message, Some(message_id)).await;
+            }
+            ChatCommand::Start(category) => self.start_random_round(category, message_id).await,
+            ChatCommand::Skip if in_progress => {
+                self.end_round_from_chat("chat.round-skipped").await;
+                self.start_random_round(None, message_id).await;
+            }
+            ChatCommand::Skip => {}
+            ChatCommand::Reveal => self.reveal_hint().await,
+            ChatCommand::End => self.end_round_from_chat("chat.round-ended").await,
+            ChatCommand::Score => {
+                let leaderboard = self.scoreboard.leaderboard();
+                let message = if leaderboard.is_empty() {
+                    self.catalog.text("chat.leaderboard-empty").to_owned()
+                } else {
+                    let players: Vec<String> = leaderboard
+                        .iter()
+                        .take(5)
+                        .enumerate()
+                        .map(|(i, player)| {
+                            format!("{}. {} ({})", i + 1, player.player_name, player.points)
+                        })
+                        .collect();
+                    let players = players.join(", ");
+                    self.catalog
+                        .format("chat.leaderboard", &[("players", &players)])
+                };
+                self.send_chat_message(&message, Some(message_id)).await;
+            }
+        }
+    }
+
+    /// Random word of the bank that the rules accept, like a typed word
+    fn pick_word(&mut self, category: Option<&str>) -> Option<RandomWord> {
+        let rules = &self.rules;
+        self.words
+            .pick(category, |word| rules.check_word(word).is_ok())
+    }
+
+    async fn start_random_round(&mut self, category: Option<String>, message_id: &str) {
+        let Some(random_word) = self.pick_word(category.as_deref()) else {
+            let message = self.catalog.text("chat.no-words-left");
+            self.send_chat_message(message, Some(message_id)).await;
+            return;
+        };
+        self.start_game(random_word.word, Some(random_word.category))
+            .await;
+    }
+
+    async fn end_round_from_chat(&mut self, message_key: &'static str) {
+        let Some(game) = &mut self.game else {
+            return;
+        };
+        if game.outcome() != GameOutcome::InProgress {
+            return;
+        }
+        game.end();
+        let message = self
+            .catalog
+            .format(message_key, &[("word", &game.get_word())]);
+        self.publish_state();
+        self.send_chat_message(&message, None).await;
+        self.finish_round(GameOutcome::Lost, None).await;
+    }
+
+    async fn reveal_hint(&mut self) {
+        let letter = self.game.as_mut().and_then(Game::reveal_hint);
+        if let Some(letter) = letter {
+            self.publish_state();
+            let message = self
+                .catalog
+                .format("chat.letter-hint", &[("letter", &letter)]);
+            self.send_chat_message(&message, None).await;
+        }
+    }
+
+    async fn record_guess(
+        &mut self,
+        player_id: &str,
+        player_name: &str,
+        result: GuessResult,
+        outcome: GameOutcome,
+    ) {
+        if result == GuessResult::Ignored {
+            return;
+        }
+        self.publish_event(GameEvent::Guess {
+            player_name: player_name.to_owned(),
+            result,
+        });
+        let points = self
+            .scoreboard
+            .record(player_id, player_name, result, outcome);
+        self.stats
+            .record(player_id, player_name, result, points.unwrap_or(0));
+        if points.is_some() {
+            let leaderboard = self.scoreboard.leaderboard();
+            self.publish_event(GameEvent::LeaderboardChanged(leaderboard));
+        }
+        if outcome != GameOutcome::InProgress {
+            let won = outcome == GameOutcome::Won;
+            let announcement = if won {
+                Announcement::Win
+            } else {
+                Announcement::Loss
+            };
+            self.announce(announcement, Some(player_name), None).await;
+            self.finish_round(outcome, won.then_some(player_id)).await;
+        }
+    }
+
+    async fn finish_round(&mut self, outcome: GameOutcome, winner: Option<&str>) {
+        self.timer = None;
+        self.stats.end_round(winner);
+        if let Err(err) = self.stats.save().await {
+            crate::log_error!("[actor TwitchGameActor]: {err}");
+        }
+        self.publish_event(GameEvent::RoundFinished { outcome });
+    }
+
+    async fn handle_timer_events(&mut self) {
+        let Some(timer) = &mut self.timer else {
+            return;
+        };
+        for event in timer.poll(Instant::now()) {
+            match event {
+                TimerEvent::Hint => self.reveal_hint().await,
+                TimerEvent::Warning(seconds) => {
+                    let message = self
+                        .catalog
+                        .format("chat.time-warning", &[("seconds", &seconds)]);
+                    self.send_chat_message(&message, None).await;
+                }
+                TimerEvent::TimeOut => {
+                    if let Some(game) = &mut self.game {
+                        game.time_out();
+                    }
+                    self.announce(Announcement::TimeOut, None, None).await;
+                    self.finish_round(GameOutcome::Lost, None).await;
+                }
+            }
+        }
+        self.publish_state();
+    }
+
+    /// Sends the message of the event unless its template silences it
+    async fn announce(
+        &mut self,
+        announcement: Announcement,
+        player: Option<&str>,
+        reply_to: Option<&str>,
+    ) {
+        let Some(game) = &self.game else {
+            return;
+        };
+        // the word is only revealed once the round is over
+        let word = if game.outcome() == GameOutcome::InProgress {
+            game.get_actual_word()
+        } else {
+            game.get_word()
+        };
+        let values = Placeholders {
+            word: &word,
+            category: self.category.as_deref(),
+            command: self.command.trim_end(),
+            player,
+            winner: player.filter(|_| announcement == Announcement::Win),
+            lives: game.remaining_lives(),
+            max_lives: game.max_lives(),
+            time_left: self
+                .timer
+                .as_ref()
+                .map(|timer| timer.remaining_seconds(Instant::now())),
+        };
+        if let Some(message) = self.announcer.announce(announcement, &values) {
+            self.send_chat_message(&message, reply_to).await;
+        }
+    }
+
+    async fn send_chat_message(&mut self, message: &str, reply_to: Option<&str>) {
+        let chat = &self.chat;
+        let result = self
+            .retry_policy
+            .run("Sending message", move || async move {
+                match reply_to {
+                    Some(message_id) => chat.reply(message, message_id).await,
+                    None => chat.send(message).await,
+                }
+            })
+            .await;
+        if let Err(err) = result {
+            self.publish_error(ColgadoLogicError::SendError { err });
+        }
+    }
+
+    fn publish_error(&self, err: ColgadoLogicError) {
+        crate::log_error!("[actor TwitchGameActor]: {err}");
+        self.publish_event(GameEvent::Error(Arc::new(err)));
+    }
+
+    fn game_view(&self) -> Option<GameView> {
+        self.game.as_ref().map(|game| {
+            let mut game_view = GameView::from(game);
+            game_view.remaining_seconds = self
+                .timer
+                .as_ref()
+                .map(|timer| timer.remaining_seconds(Instant::now()))
+                .or(game.is_timed_out().then_some(0));
+            game_view
+        })
+    }
+
+    fn publish_state(&self) {
+        self.state_sender.send_replace(self.game_view());
+    }
+
+    fn publish_event(&self, event: GameEvent) {
+        // there may be no subscribers, the event is discarded then
+        let _ = self.events_sender.send(event);
+    }
+
     fn valid_player_message<'a>(&self, mut message_text: &'a str) -> Option<Vec<&'a str>> {
         if message_text.is_empty() {
             return None;
@@ -111,81 +494,240 @@ impl TwitchGameActor {
         }
     }
 
-    fn handle_command_message(&mut self, message: CommandMessage) -> bool {
+    async fn handle_command_message(&mut self, message: CommandMessage) -> bool {
         match message {
             CommandMessage::GetSessionId { sender } => {
-                let id = self.session_id.get().cloned();
+                let id = self.session_id.clone();
                 let _ = sender.send(id);
             }
             CommandMessage::GetGameState { sender } => {
-                let game_view: Option<GameView> = self.game.as_ref().map(GameView::from);
-                let _ = sender.send(game_view);
+                let _ = sender.send(self.game_view());
             }
             CommandMessage::SetGameWord { word, sender } => {
-                let game = Game::new(word);
-                let word = game.get_actual_word();
-                self.game = Some(game);
-                let _ = sender.send(word);
+                let result = match self.rules.check_word(&word) {
+                    Ok(()) => Ok(self.start_game(word, None).await),
+                    Err(err) => Err(err.into()),
+                };
+                let _ = sender.send(result);
+            }
+            CommandMessage::GetLeaderboard { sender } => {
+                let _ = sender.send(self.scoreboard.leaderboard());
+            }
+            CommandMessage::SetRandomWord { category, sender } => {
+                let random_word = match self.pick_word(category.as_deref()) {
+                    Some(RandomWord { word, category }) => {
+                        let word = self.start_game(word, Some(category.clone())).await;
+                        Some(RandomWord { word, category })
+                    }
+                    None => None,
+                };
+                let _ = sender.send(random_word);
+            }
+            CommandMessage::GetCategories { sender } => {
+                let _ = sender.send(self.words.categories());
+            }
+            CommandMessage::ApplySettings { settings, sender } => {
+                self.apply_settings(*settings);
+                let _ = sender.send(());
+            }
+            CommandMessage::Shutdown { sender } => {
+                self.flush_chat_messages().await;
+                self.unsubscribe().await;
+                self.shutdown = Some(sender);
+                return false;
             }
         }
         true
     }
 
+    /// Sends the chat messages still queued, anything else is dropped
+    async fn flush_chat_messages(&mut self) {
+        while let Ok(message) = self.receiver.try_recv() {
+            if let GeneralMessage::TwitchSendMessage(message) = message {
+                self.send_chat_message(&message, None).await;
+            }
+        }
+    }
+
+    async fn unsubscribe(&mut self) {
+        let Some(session_id) = self.session_id.take() else {
+            return;
+        };
+        if let Err(err) = self.chat.unsubscribe(&session_id).await {
+            crate::log_error!("[actor TwitchGameActor]: Error unsubscribing: {err}");
+        }
+    }
+
+    fn apply_settings(&mut self, settings: Settings) {
+        let catalog = Catalog::new(settings.chat_language());
+        self.announcer = std::mem::take(&mut self.announcer)
+            .with_catalog(catalog)
+            .with_toggles(settings.game.announce);
+        self.catalog = catalog;
+        self.scoreboard.set_weights(settings.game.scoring);
+        // the timer is read from the rules when a round starts, the limiter keeps
+        // the guesses of the players unless its options changed
+        let limits = settings.game.enabled_limits();
+        if limits != self.rules.enabled_limits() {
+            self.limiter = limits.cloned().map(GuessLimiter::new);
+        }
+        self.rules = settings.game;
+        self.command = settings.command + " ";
+    }
+
+    /// Announces the round, returns the hidden word
+    async fn start_game(&mut self, word: String, category: Option<String>) -> String {
+        let game = self.rules.new_game(word);
+        let word = game.get_actual_word();
+        self.game = Some(game);
+        self.category = category;
+        if let Some(limiter) = &mut self.limiter {
+            limiter.new_round();
+        }
+        self.timer = self
+            .rules
+            .enabled_timer()
+            .map(|config| RoundTimer::start(config, Instant::now()));
+        self.publish_state();
+        self.announce(Announcement::RoundStart, None, None).await;
+        word
+    }
+
     pub async fn run(mut self) {
-        while let Some(message) = self.receiver.recv().await {
-            let continue_loop = self.handle(message).await;
+        let mut timer_ticks = tokio::time::interval(TIMER_TICK);
+        timer_ticks.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        loop {
+            let continue_loop = tokio::select! {
+                message = self.receiver.recv() => match message {
+                    Some(message) => self.handle(message).await,
+                    None => false,
+                },
+                _ = timer_ticks.tick(), if self.timer.is_some() => {
+                    self.handle_timer_events().await;
+                    true
+                }
+            };
             if !continue_loop {
                 break;
             }
         }
-        println!("[actor TwitchGameActor]: Finished");
+        if let Err(err) = self.stats.save().await {
+            crate::log_error!("[actor TwitchGameActor]: {err}");
+        }
+        if let Some(sender) = self.shutdown.take() {
+            let _ = sender.send(());
+        }
+        crate::log!("[actor TwitchGameActor]: Finished");
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct TwitchGameHandle {
     sender: mpsc::UnboundedSender<GeneralMessage>,
+    state_receiver: watch::Receiver<Option<GameView>>,
+    events_sender: broadcast::Sender<GameEvent>,
 }
 
 impl TwitchGameHandle {
-    pub fn new_and_joinhandle(
-        user_info: info::User,
-        bot_info: info::Bot,
-        command: String,
+    pub fn new_and_joinhandle<Transport: ChatTransport>(
+        chat: Transport,
+        setup: GameSetup,
     ) -> (Self, tokio::task::JoinHandle<()>) {
         let (send, recv) = mpsc::unbounded_channel();
-        let actor = TwitchGameActor::new(recv, user_info, bot_info, command);
+        let (state_sender, state_receiver) = watch::channel(None);
+        let (events_sender, _) = broadcast::channel(EVENTS_CAPACITY);
+        let actor =
+            TwitchGameActor::from_setup(recv, state_sender, events_sender.clone(), chat, setup);
         let task = tokio::spawn(actor.run());
-        (Self { sender: send }, task)
+        let handle = Self {
+            sender: send,
+            state_receiver,
+            events_sender,
+        };
+        (handle, task)
+    }
+
+    /// Receiver notified every time the game changes
+    pub fn watch_game_state(&self) -> watch::Receiver<Option<GameView>> {
+        self.state_receiver.clone()
+    }
+
+    /// Receiver of the events published from now on
+    pub fn subscribe_events(&self) -> broadcast::Receiver<GameEvent> {
+        self.events_sender.subscribe()
     }
 
     async fn send_and_recv<MSType>(
         &self,
         message: CommandMessage,
         recv: oneshot::Receiver<MSType>,
-    ) -> MSType {
+    ) -> Result<MSType, ColgadoLogicError> {
         let message = GeneralMessage::CommandMessage(message);
         self.sender
             .send(message)
-            .expect("Error: Twitch Actor killed");
-        recv.await.expect("Error: Twitch Actor killed")
+            .map_err(|_| ColgadoLogicError::ActorStopped)?;
+        recv.await.map_err(|_| ColgadoLogicError::ActorStopped)
     }
 
-    pub async fn get_id(&self) -> Option<String> {
+    pub async fn get_id(&self) -> Result<Option<String>, ColgadoLogicError> {
         let (send, recv) = oneshot::channel::<Option<String>>();
         let message = CommandMessage::GetSessionId { sender: send };
         self.send_and_recv(message, recv).await
     }
 
-    pub async fn get_game_state(&self) -> Option<GameView> {
+    pub async fn get_game_state(&self) -> Result<Option<GameView>, ColgadoLogicError> {
         let (send, recv) = oneshot::channel::<Option<GameView>>();
         let message = CommandMessage::GetGameState { sender: send };
         self.send_and_recv(message, recv).await
     }
 
-    pub async fn set_game_word(&self, word: String) -> String {
-        let (send, recv) = oneshot::channel::<String>();
+    pub async fn set_game_word(&self, word: String) -> Result<String, ColgadoLogicError> {
+        let (send, recv) = oneshot::channel::<Result<String, ColgadoLogicError>>();
         let message = CommandMessage::SetGameWord { word, sender: send };
+        self.send_and_recv(message, recv).await?
+    }
+
+    /// Errors with `NoWordsLeft` when every word of the category has been played
+    pub async fn set_random_word(
+        &self,
+        category: Option<String>,
+    ) -> Result<RandomWord, ColgadoLogicError> {
+        let (send, recv) = oneshot::channel();
+        let message = CommandMessage::SetRandomWord {
+            category,
+            sender: send,
+        };
+        self.send_and_recv(message, recv)
+            .await?
+            .ok_or(ColgadoLogicError::NoWordsLeft)
+    }
+
+    pub async fn get_categories(&self) -> Result<Vec<String>, ColgadoLogicError> {
+        let (send, recv) = oneshot::channel();
+        let message = CommandMessage::GetCategories { sender: send };
+        self.send_and_recv(message, recv).await
+    }
+
+    pub async fn get_leaderboard(&self) -> Result<Leaderboard, ColgadoLogicError> {
+        let (send, recv) = oneshot::channel::<Leaderboard>();
+        let message = CommandMessage::GetLeaderboard { sender: send };
+        self.send_and_recv(message, recv).await
+    }
+
+    /// Applies the command, the rules and the chat language without connecting again
+    pub async fn apply_settings(&self, settings: Settings) -> Result<(), ColgadoLogicError> {
+        let (send, recv) = oneshot::channel();
+        let message = CommandMessage::ApplySettings {
+            settings: Box::new(settings),
+            sender: send,
+        };
+        self.send_and_recv(message, recv).await
+    }
+
+    /// Stops the actor once the queued chat messages are sent and the stats saved
+    pub async fn shutdown(&self) -> Result<(), ColgadoLogicError> {
+        let (send, recv) = oneshot::channel();
+        let message = CommandMessage::Shutdown { sender: send };
         self.send_and_recv(message, recv).await
     }
 
@@ -209,3 +751,246 @@ impl TwitchGameHandle {
         }
     }
 }
+
+impl ChatSink for TwitchGameHandle {
+    fn deliver(&self, message: GeneralMessage) {
+        self.non_sleeping_send(message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actors::transport::{MemoryTransport, SentMessage};
+    use crate::i18n::Language;
+    use crate::limits::GuessLimitsConfig;
+    use crate::models::eventsub::ChatRole;
+    use crate::timer::TimerConfig;
+    use crate::word::{DEFAULT_LIVES, DEFAULT_SOLVE_PENALTY};
+    use crate::word_bank::BankWord;
+
+    fn setup() -> GameSetup {
+        GameSetup {
+            command: "!colgado".to_owned(),
+            ..GameSetup::default()
+        }
+    }
+
+    /// Actor driven by calling its methods, without a task
+    fn actor(setup: GameSetup) -> (TwitchGameActor<MemoryTransport>, MemoryTransport) {
+        let transport = MemoryTransport::default();
+        let (_, receiver) = mpsc::unbounded_channel();
+        let (state_sender, _) = watch::channel(None);
+        let (events_sender, _) = broadcast::channel(EVENTS_CAPACITY);
+        let actor = TwitchGameActor::from_setup(
+            receiver,
+            state_sender,
+            events_sender,
+            transport.clone(),
+            setup,
+        );
+        (actor, transport)
+    }
+
+    fn player_message(message_id: &str, text: &str) -> TwitchMessage {
+        chat_message(message_id, text, ChatRole::Viewer)
+    }
+
+    fn chat_message(message_id: &str, text: &str, role: ChatRole) -> TwitchMessage {
+        TwitchMessage::PlayerMessage {
+            message_text: text.to_owned(),
+            message_id: message_id.to_owned(),
+            player_id: "1".to_owned(),
+            player_name: "ana".to_owned(),
+            role,
+        }
+    }
+
+    fn texts(transport: &MemoryTransport) -> Vec<String> {
+        transport.sent().into_iter().map(|sent| sent.text).collect()
+    }
+
+    #[tokio::test]
+    async fn plays_through_transport() {
+        let transport = MemoryTransport::default();
+        let (handle, _) = TwitchGameHandle::new_and_joinhandle(transport.clone(), setup());
+        handle.deliver(GeneralMessage::TwitchMessage(
+            TwitchMessage::WelcomeMessage {
+                session_id: "session".to_owned(),
+                keepalive_timeout_seconds: None,
+            },
+        ));
+        handle.set_game_word("casa".to_owned()).await.unwrap();
+        handle.deliver(GeneralMessage::TwitchMessage(player_message("1", "a")));
+        handle.deliver(GeneralMessage::TwitchMessage(player_message(
+            "2",
+            "!colgado cosa",
+        )));
+        handle.send_message("Hola".to_owned()).await;
+
+        // the messages are handled in order, so the state is read after the guesses
+        let game = handle.get_game_state().await.unwrap().unwrap();
+        assert_eq!(game.word, "_a_a");
+        assert_eq!(transport.subscriptions(), ["session"]);
+        let reply = SentMessage {
+            text: "Esa no es la palabra".to_owned(),
+            reply_to: Some("2".to_owned()),
+        };
+        let message = SentMessage {
+            text: "Hola".to_owned(),
+            reply_to: None,
+        };
+        let sent = transport.sent();
+        assert!(sent[0]
+            .text
+            .starts_with("Comenzando partida, escribe el comando !colgado "));
+        assert_eq!(sent[1..], [reply, message]);
+    }
+
+    #[tokio::test]
+    async fn shuts_down() {
+        let transport = MemoryTransport::default();
+        let (handle, task) = TwitchGameHandle::new_and_joinhandle(transport.clone(), setup());
+        handle.deliver(GeneralMessage::TwitchMessage(
+            TwitchMessage::WelcomeMessage {
+                session_id: "session".to_owned(),
+                keepalive_timeout_seconds: None,
+            },
+        ));
+        let (send, recv) = oneshot::channel();
+        handle.non_sleeping_send(GeneralMessage::CommandMessage(CommandMessage::Shutdown {
+            sender: send,
+        }));
+        // queued after the shutdown, still sent before the actor stops
+        handle.send_message("Adiós".to_owned()).await;
+        recv.await.unwrap();
+        task.await.unwrap();
+
+        assert_eq!(transport.sent()[0].text, "Adiós");
+        assert!(transport.subscriptions().is_empty());
+        let result = handle.get_game_state().await;
+        assert!(matches!(result, Err(ColgadoLogicError::ActorStopped)));
+    }
+
+    #[tokio::test]
+    async fn applies_settings() {
+        let (mut actor, transport) = actor(setup());
+        actor.start_game("casa".to_owned(), None).await;
+
+        actor.apply_settings(Settings {
+            command: "!horca".to_owned(),
+            chat_language: Some(Language::En),
+            game: GameConfig {
+                lives: 3,
+                ..GameConfig::default()
+            },
+            ..Settings::default()
+        });
+        // the round in progress keeps its rules
+        assert_eq!(actor.game_view().unwrap().max_lives, DEFAULT_LIVES);
+
+        actor.start_game("perro".to_owned(), None).await;
+        actor
+            .handle_twitch_message(player_message("1", "!colgado perro"))
+            .await;
+        actor
+            .handle_twitch_message(player_message("2", "!horca gato"))
+            .await;
+        let game = actor.game_view().unwrap();
+        assert_eq!((game.lives, game.max_lives), (1, 3));
+
+        let sent = texts(&transport);
+        assert!(sent[1].contains("!horca"));
+        assert_eq!(sent[2], "That is not the word");
+
+        let limits = GuessLimitsConfig {
+            max_guesses_per_round: Some(1),
+            ..GuessLimitsConfig::default()
+        };
+        actor.apply_settings(Settings {
+            command: "!horca".to_owned(),
+            game: GameConfig {
+                timer: Some(TimerConfig::default()),
+                limits: Some(limits),
+                ..GameConfig::default()
+            },
+            ..Settings::default()
+        });
+        actor.start_game("casa".to_owned(), None).await;
+        actor.handle_twitch_message(player_message("3", "a")).await;
+        actor.handle_twitch_message(player_message("4", "c")).await;
+        let game = actor.game_view().unwrap();
+        assert_eq!(game.word, "_a_a");
+        assert_eq!(
+            game.remaining_seconds,
+            Some(TimerConfig::default().round_seconds)
+        );
+    }
+
+    #[tokio::test]
+    async fn chat_commands() {
+        let mut words = WordBank::default();
+        words.add_category("colores".to_owned(), vec![BankWord::estimated("amarillo")]);
+        let (mut actor, transport) = actor(GameSetup { words, ..setup() });
+        // a viewer can not start the round, and there is nothing to skip
+        actor
+            .handle_twitch_message(player_message("1", "!colgado empezar"))
+            .await;
+        let skip = chat_message("2", "!colgado saltar", ChatRole::Moderator);
+        actor.handle_twitch_message(skip).await;
+        // only categories follow the start subcommand, and only the Spanish names work
+        let start = chat_message("3", "!colgado empezar plantas", ChatRole::Moderator);
+        actor.handle_twitch_message(start).await;
+        let start = chat_message("4", "!colgado start", ChatRole::Moderator);
+        actor.handle_twitch_message(start).await;
+        assert!(actor.game_view().is_none());
+
+        let start = chat_message("5", "!colgado empezar Colores", ChatRole::Moderator);
+        actor.handle_twitch_message(start).await;
+        assert_eq!(actor.game_view().unwrap().word, "________");
+        // nor end it, the subcommand is a wrong guess then, both have 8 letters
+        actor
+            .handle_twitch_message(player_message("6", "!colgado terminar"))
+            .await;
+        let game = actor.game_view().unwrap();
+        assert_eq!(game.outcome, GameOutcome::InProgress);
+        assert_eq!(game.lives, game.max_lives - DEFAULT_SOLVE_PENALTY);
+
+        let end = chat_message("7", "!colgado terminar", ChatRole::Broadcaster);
+        actor.handle_twitch_message(end).await;
+        let game = actor.game_view().unwrap();
+        assert_eq!(game.outcome, GameOutcome::Lost);
+        assert!(game.ended);
+
+        let sent = texts(&transport);
+        assert_eq!(sent.len(), 3);
+        assert!(sent[0].ends_with("Pista: la categoría es colores"));
+        assert_eq!(sent[1], "Esa no es la palabra");
+        assert_eq!(sent[2], "Ronda terminada, la palabra era amarillo");
+    }
+
+    #[tokio::test]
+    async fn limits_guesses() {
+        let limits = GuessLimitsConfig {
+            max_guesses_per_round: Some(1),
+            replies: true,
+            ..GuessLimitsConfig::default()
+        };
+        let rules = GameConfig {
+            limits: Some(limits),
+            ..GameConfig::default()
+        };
+        let (mut actor, transport) = actor(GameSetup { rules, ..setup() });
+        actor.start_game("casa".to_owned(), None).await;
+        actor.handle_twitch_message(player_message("1", "a")).await;
+        actor.handle_twitch_message(player_message("2", "c")).await;
+        actor.handle_twitch_message(player_message("3", "s")).await;
+
+        assert_eq!(actor.game_view().unwrap().word, "_a_a");
+        let reply = SentMessage {
+            text: "Ya has gastado tus intentos de esta ronda".to_owned(),
+            reply_to: Some("2".to_owned()),
+        };
+        assert_eq!(transport.sent()[1..], [reply]);
+    }
+}
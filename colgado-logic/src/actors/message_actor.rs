@@ -1,73 +1,572 @@
+use std::{future::Future, pin::Pin, time::Duration};
+
 use super::{
-    game_actor::TwitchGameHandle,
-    models::messages::{GeneralMessage, TwitchMessage},
-    WebSocket, WsReceiver,
+    models::{
+        events::ConnectionStatus,
+        messages::{CommandMessage, GeneralMessage, TwitchMessage},
+    },
+    transport::ChatSink,
+    WebSocket,
 };
 
+use crate::{errors::ColgadoLogicError, retry::RetryPolicy};
 use futures_util::StreamExt;
-use tokio::sync::mpsc;
-use tokio_tungstenite::tungstenite::Message;
+use tokio::{
+    sync::{mpsc, oneshot},
+    time::Instant,
+};
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{
+        self,
+        protocol::{frame::coding::CloseCode, CloseFrame},
+        Message,
+    },
+};
+
+/// Time waited after the keepalive timeout before considering the connection lost
+const KEEPALIVE_MARGIN: Duration = Duration::from_secs(5);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+/// Tries to open a new session after the connection is lost, about a minute and a half
+const RECONNECT_POLICY: RetryPolicy = RetryPolicy {
+    attempts: 8,
+    delay: Duration::from_secs(1),
+};
+
+/// Connection being opened, polled by the actor loop so it can still be stopped
+type PendingConnection =
+    Pin<Box<dyn Future<Output = Result<WebSocket, tungstenite::Error>> + Send>>;
+
+/// New session being opened after the connection was lost
+struct Recovery {
+    attempt: u32,
+    connection: PendingConnection,
+}
 
 struct TwitchMessageActor<Handle> {
-    twitch_receiver: WsReceiver,
+    /// Missing while a new session is being opened
+    twitch_socket: Option<WebSocket>,
+    /// EventSub server, used to open a new session when the connection is lost
+    url: String,
+    /// Connection replaced after a reconnect message, read until Twitch closes it
+    /// since it can still have notifications
+    old_socket: Option<WebSocket>,
+    /// Connection to the URL of a reconnect message being opened
+    reconnect_connection: Option<PendingConnection>,
+    /// Connection opened after a reconnect message, used once its welcome arrives
+    reconnect_socket: Option<WebSocket>,
+    /// The next welcome belongs to a reconnection, so there is no need to subscribe again
+    reconnecting: bool,
+    recovery: Option<Recovery>,
+    reconnect_policy: RetryPolicy,
+    keepalive_timeout: Option<Duration>,
+    last_message: Instant,
     handle: Handle,
     handle_receiver: mpsc::Receiver<GeneralMessage>,
 }
 
-impl TwitchMessageActor<TwitchGameHandle> {
+impl<Handle: ChatSink> TwitchMessageActor<Handle> {
     pub fn new(
-        twitch_receiver: WsReceiver,
-        handle: TwitchGameHandle,
+        twitch_socket: WebSocket,
+        url: String,
+        handle: Handle,
         handle_receiver: mpsc::Receiver<GeneralMessage>,
     ) -> Self {
         Self {
-            twitch_receiver,
+            twitch_socket: Some(twitch_socket),
+            url,
+            old_socket: None,
+            reconnect_connection: None,
+            reconnect_socket: None,
+            reconnecting: false,
+            recovery: None,
+            reconnect_policy: RECONNECT_POLICY,
+            keepalive_timeout: None,
+            last_message: Instant::now(),
             handle,
             handle_receiver,
         }
     }
 
-    async fn handle_twitch_message(&mut self, message: Message) {
-        let twitch_message = TwitchMessage::from(message);
-        let general_message = match &twitch_message {
+    /// Broken or unknown frames are reported to the game, which publishes them
+    fn parse_message(&mut self, message: Message) -> Option<TwitchMessage> {
+        self.last_message = Instant::now();
+        match TwitchMessage::try_from(message) {
+            Ok(twitch_message) => Some(twitch_message),
+            Err(err) => {
+                self.handle.deliver(GeneralMessage::Error(err.into()));
+                None
+            }
+        }
+    }
+
+    fn handle_twitch_message(&mut self, message: Message) {
+        if let Some(twitch_message) = self.parse_message(message) {
+            self.forward_message(twitch_message);
+        }
+    }
+
+    /// Handles the messages of the new connection, once its welcome arrives it replaces the
+    /// current one, which is kept until Twitch closes it
+    fn handle_reconnect_message(&mut self, message: Message) {
+        let Some(twitch_message) = self.parse_message(message) else {
+            return;
+        };
+        if let TwitchMessage::WelcomeMessage { .. } = twitch_message
+            && let Some(reconnect_socket) = self.reconnect_socket.take()
+        {
+            self.old_socket = self.twitch_socket.replace(reconnect_socket);
+        }
+        self.forward_message(twitch_message);
+    }
+
+    fn forward_message(&mut self, twitch_message: TwitchMessage) {
+        let general_message = match twitch_message {
             TwitchMessage::None | TwitchMessage::Other { mesage: _ } => {
                 return;
             }
+            TwitchMessage::ReconnectMessage { reconnect_url } => {
+                self.reconnect_connection = Some(open(reconnect_url, Duration::ZERO));
+                return;
+            }
+            TwitchMessage::WelcomeMessage {
+                session_id,
+                keepalive_timeout_seconds,
+            } => {
+                self.keepalive_timeout = keepalive_timeout_seconds.map(Duration::from_secs);
+                self.send_status(ConnectionStatus::Connected);
+                let twitch_message = if self.reconnecting {
+                    self.reconnecting = false;
+                    TwitchMessage::SessionReconnected { session_id }
+                } else {
+                    TwitchMessage::WelcomeMessage {
+                        session_id,
+                        keepalive_timeout_seconds,
+                    }
+                };
+                GeneralMessage::TwitchMessage(twitch_message)
+            }
             _ => GeneralMessage::TwitchMessage(twitch_message),
         };
-        self.handle.non_sleeping_send(general_message);
+        self.handle.deliver(general_message);
+    }
+
+    /// Drops the connections and opens a new session, the game actor subscribes again
+    /// when its welcome arrives
+    fn lose_connection(&mut self) {
+        self.send_status(ConnectionStatus::Reconnecting);
+        self.twitch_socket = None;
+        self.old_socket = None;
+        self.reconnect_connection = None;
+        self.reconnect_socket = None;
+        self.reconnecting = false;
+        self.keepalive_timeout = None;
+        self.recovery = Some(Recovery {
+            attempt: 1,
+            connection: open(self.url.clone(), Duration::ZERO),
+        });
+    }
+
+    /// Tries again after a delay, the error is given back once the attempts run out
+    fn retry_recovery(&mut self, err: tungstenite::Error) -> Option<tungstenite::Error> {
+        let recovery = self.recovery.as_mut()?;
+        crate::log_error!(
+            "[actor TwitchMessageActor]: Error reconnecting ({}/{}): {err}",
+            recovery.attempt,
+            self.reconnect_policy.attempts
+        );
+        if recovery.attempt >= self.reconnect_policy.attempts {
+            self.recovery = None;
+            return Some(err);
+        }
+        let delay = self
+            .reconnect_policy
+            .delay(recovery.attempt)
+            .min(MAX_RECONNECT_DELAY);
+        recovery.attempt += 1;
+        recovery.connection = open(self.url.clone(), delay);
+        None
+    }
+
+    /// Sends the close frame and waits for the one of the server, which ends the stream
+    async fn close(&mut self) {
+        self.old_socket = None;
+        self.reconnect_connection = None;
+        self.reconnect_socket = None;
+        self.recovery = None;
+        let Some(twitch_socket) = self.twitch_socket.as_mut() else {
+            return;
+        };
+        let frame = CloseFrame {
+            code: CloseCode::Normal,
+            reason: "".into(),
+        };
+        if let Err(err) = twitch_socket.close(Some(frame)).await {
+            crate::log_error!("[actor TwitchMessageActor]: Error closing: {err}");
+            return;
+        }
+        while let Some(Ok(_)) = twitch_socket.next().await {}
+    }
+
+    fn send_status(&self, status: ConnectionStatus) {
+        self.handle
+            .deliver(GeneralMessage::ConnectionStatus(status));
+    }
+
+    fn keepalive_deadline(&self) -> Option<Instant> {
+        let timeout = self.keepalive_timeout?;
+        Some(self.last_message + timeout + KEEPALIVE_MARGIN)
     }
 
     pub async fn run(mut self) {
+        let mut shutdown = None;
         loop {
+            let keepalive_deadline = self.keepalive_deadline();
             tokio::select! {
-               Some(Ok(message)) = self.twitch_receiver.next() => {
-                   self.handle_twitch_message(message).await;
+               message = next_message(&mut self.twitch_socket) => match message {
+                   Some(Ok(message)) => self.handle_twitch_message(message),
+                   Some(Err(err)) => {
+                       crate::log_error!("[actor TwitchMessageActor]: {err}");
+                       self.lose_connection();
+                   }
+                   None => match self.reconnect_socket.take() {
+                       // Twitch closed the old connection before the new welcome arrived
+                       Some(reconnect_socket) => self.twitch_socket = Some(reconnect_socket),
+                       None => self.lose_connection(),
+                   },
+               },
+               message = next_message(&mut self.old_socket) => match message {
+                   Some(Ok(message)) => self.handle_twitch_message(message),
+                   Some(Err(_)) | None => self.old_socket = None,
+               },
+               message = next_message(&mut self.reconnect_socket) => match message {
+                   Some(Ok(message)) => self.handle_reconnect_message(message),
+                   Some(Err(err)) => {
+                       crate::log_error!("[actor TwitchMessageActor]: Error reconnecting: {err}");
+                       self.reconnect_socket = None;
+                       self.reconnecting = false;
+                   }
+                   None => {
+                       self.reconnect_socket = None;
+                       self.reconnecting = false;
+                   }
+               },
+               result = connection(&mut self.reconnect_connection) => {
+                   self.reconnect_connection = None;
+                   match result {
+                       Ok(reconnect_socket) => {
+                           self.reconnect_socket = Some(reconnect_socket);
+                           self.reconnecting = true;
+                       }
+                       Err(err) => {
+                           crate::log_error!("[actor TwitchMessageActor]: Error reconnecting: {err}");
+                           self.lose_connection();
+                       }
+                   }
+               },
+               result = recovery(&mut self.recovery) => match result {
+                   Ok(twitch_socket) => {
+                       self.recovery = None;
+                       self.twitch_socket = Some(twitch_socket);
+                       self.last_message = Instant::now();
+                   }
+                   Err(err) => {
+                       if let Some(err) = self.retry_recovery(err) {
+                           let err = ColgadoLogicError::ConnectionError { err };
+                           self.handle.deliver(GeneralMessage::Error(err));
+                           break;
+                       }
+                   }
+               },
+               _ = sleep_until(keepalive_deadline) => {
+                   crate::log_error!("[actor TwitchMessageActor]: Keepalive timeout");
+                   self.lose_connection();
                }
-               Some(_) = self.handle_receiver.recv() => {
-
+               Some(message) = self.handle_receiver.recv() => {
+                   if let GeneralMessage::CommandMessage(CommandMessage::Shutdown { sender }) = message {
+                       self.close().await;
+                       shutdown = Some(sender);
+                       break;
+                   }
                }
                else => break,
             }
         }
 
-        println!("[actor TwitchMessageActor]: Finished");
+        self.send_status(ConnectionStatus::Disconnected);
+        if let Some(sender) = shutdown {
+            let _ = sender.send(());
+        }
+        crate::log!("[actor TwitchMessageActor]: Finished");
     }
 }
+
+/// Connects to the URL after the delay
+fn open(url: String, delay: Duration) -> PendingConnection {
+    Box::pin(async move {
+        tokio::time::sleep(delay).await;
+        let (ws_stream, _) = connect_async(url).await?;
+        Ok(ws_stream)
+    })
+}
+
+/// Without deadline waits forever
+async fn sleep_until(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Next message of an optional connection, without connection waits forever
+async fn next_message(
+    socket: &mut Option<WebSocket>,
+) -> Option<Result<Message, tungstenite::Error>> {
+    match socket {
+        Some(socket) => socket.next().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Result of an optional connection being opened, without connection waits forever
+async fn connection(
+    pending: &mut Option<PendingConnection>,
+) -> Result<WebSocket, tungstenite::Error> {
+    match pending {
+        Some(connection) => connection.await,
+        None => std::future::pending().await,
+    }
+}
+
+async fn recovery(recovery: &mut Option<Recovery>) -> Result<WebSocket, tungstenite::Error> {
+    match recovery {
+        Some(recovery) => (&mut recovery.connection).await,
+        None => std::future::pending().await,
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct TwitchMessageHandle {
-    _sender: mpsc::Sender<GeneralMessage>,
+    sender: mpsc::Sender<GeneralMessage>,
 }
 
 impl TwitchMessageHandle {
     pub fn new_and_joinhandle(
         ws_stream: WebSocket,
-        handle: TwitchGameHandle,
+        url: String,
+        handle: impl ChatSink,
     ) -> (Self, tokio::task::JoinHandle<()>) {
         let (send, recv) = mpsc::channel(100);
-        let (_, ws_recv) = ws_stream.split();
-        let actor = TwitchMessageActor::new(ws_recv, handle, recv);
+        let actor = TwitchMessageActor::new(ws_stream, url, handle, recv);
         let task = tokio::spawn(actor.run());
-        (Self { _sender: send }, task)
+        (Self { sender: send }, task)
+    }
+
+    /// Closes the WebSocket with a close frame and stops the actor
+    pub async fn shutdown(&self) -> Result<(), ColgadoLogicError> {
+        let (send, recv) = oneshot::channel();
+        let message = GeneralMessage::CommandMessage(CommandMessage::Shutdown { sender: send });
+        self.sender
+            .send(message)
+            .await
+            .map_err(|_| ColgadoLogicError::ActorStopped)?;
+        recv.await.map_err(|_| ColgadoLogicError::ActorStopped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::MessageError;
+    use futures_util::SinkExt;
+    use serde_json::json;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::MaybeTlsStream;
+
+    struct Sink(mpsc::UnboundedSender<GeneralMessage>);
+
+    impl ChatSink for Sink {
+        fn deliver(&self, message: GeneralMessage) {
+            let _ = self.0.send(message);
+        }
+    }
+
+    fn frame(message_type: &str, payload: serde_json::Value) -> Message {
+        let mut metadata = json!({
+            "message_id": "1",
+            "message_type": message_type,
+            "message_timestamp": "2023-07-19T14:56:51.634234626Z",
+        });
+        if message_type == "notification" {
+            metadata["subscription_type"] = json!("channel.chat.message");
+        }
+        let text = json!({ "metadata": metadata, "payload": payload }).to_string();
+        Message::text(text)
+    }
+
+    fn session(id: &str, reconnect_url: Option<&str>) -> Message {
+        let message_type = match reconnect_url {
+            Some(_) => "session_reconnect",
+            None => "session_welcome",
+        };
+        let payload = json!({
+            "session": { "id": id, "status": "connected", "reconnect_url": reconnect_url }
+        });
+        frame(message_type, payload)
+    }
+
+    fn notification(text: &str) -> Message {
+        let event = json!({
+            "broadcaster_user_id": "1",
+            "chatter_user_id": "2",
+            "chatter_user_login": "pepe",
+            "chatter_user_name": "Pepe",
+            "message_id": "m1",
+            "message": { "text": text },
+        });
+        frame("notification", json!({ "event": event }))
+    }
+
+    async fn connect(listener: &TcpListener) -> (WebSocket, WebSocket) {
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let (client, server) = tokio::join!(connect_async(url), accept(listener));
+        (client.unwrap().0, server)
+    }
+
+    async fn accept(listener: &TcpListener) -> WebSocket {
+        let (stream, _) = listener.accept().await.unwrap();
+        tokio_tungstenite::accept_async(MaybeTlsStream::Plain(stream))
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn reports_broken_frames() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let (client, mut server) = connect(&listener).await;
+
+        let (sink, mut messages) = mpsc::unbounded_channel();
+        let (handle, task) = TwitchMessageHandle::new_and_joinhandle(client, url, Sink(sink));
+        server.send(Message::text("{")).await.unwrap();
+        server
+            .send(frame("session_goodbye", json!({})))
+            .await
+            .unwrap();
+        server.send(session("s1", None)).await.unwrap();
+        let server = tokio::spawn(async move { while let Some(Ok(_)) = server.next().await {} });
+
+        let mut errors = Vec::new();
+        loop {
+            match messages.recv().await.unwrap() {
+                GeneralMessage::Error(ColgadoLogicError::MessageError { err }) => errors.push(err),
+                GeneralMessage::TwitchMessage(TwitchMessage::WelcomeMessage { .. }) => break,
+                _ => {}
+            }
+        }
+        assert!(matches!(
+            errors.as_slice(),
+            [
+                MessageError::Malformed { .. },
+                MessageError::UnknownType { message_type }
+            ] if message_type == "session_goodbye"
+        ));
+
+        handle.shutdown().await.unwrap();
+        task.await.unwrap();
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn gives_up_reconnecting() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let (client, mut server) = connect(&listener).await;
+        drop(listener);
+
+        let (sink, mut messages) = mpsc::unbounded_channel();
+        let (_sender, receiver) = mpsc::channel(1);
+        let mut actor = TwitchMessageActor::new(client, url, Sink(sink), receiver);
+        actor.reconnect_policy = RetryPolicy {
+            attempts: 3,
+            delay: Duration::from_millis(1),
+        };
+        let task = tokio::spawn(actor.run());
+        server.send(session("s1", None)).await.unwrap();
+        drop(server);
+        task.await.unwrap();
+
+        let mut statuses = Vec::new();
+        let mut error = None;
+        while let Ok(message) = messages.try_recv() {
+            match message {
+                GeneralMessage::ConnectionStatus(status) => statuses.push(status),
+                GeneralMessage::Error(err) => error = Some(err),
+                _ => {}
+            }
+        }
+        assert_eq!(
+            statuses,
+            [
+                ConnectionStatus::Connected,
+                ConnectionStatus::Reconnecting,
+                ConnectionStatus::Disconnected
+            ]
+        );
+        assert!(matches!(
+            error,
+            Some(ColgadoLogicError::ConnectionError { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn reads_the_old_connection_after_a_reconnect() {
+        let old_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let new_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", old_listener.local_addr().unwrap());
+        let reconnect_url = format!("ws://{}", new_listener.local_addr().unwrap());
+        let (client, mut old_server) = connect(&old_listener).await;
+
+        let (sink, mut messages) = mpsc::unbounded_channel();
+        let (handle, task) = TwitchMessageHandle::new_and_joinhandle(client, url, Sink(sink));
+        old_server.send(session("s1", None)).await.unwrap();
+        old_server
+            .send(session("s1", Some(&reconnect_url)))
+            .await
+            .unwrap();
+
+        let mut new_server = accept(&new_listener).await;
+        new_server.send(session("s2", None)).await.unwrap();
+        let new_server =
+            tokio::spawn(async move { while let Some(Ok(_)) = new_server.next().await {} });
+
+        // the new welcome is read before the last notification of the old connection
+        loop {
+            let message = messages.recv().await.unwrap();
+            if let GeneralMessage::TwitchMessage(TwitchMessage::SessionReconnected { session_id }) =
+                message
+            {
+                assert_eq!(session_id, "s2");
+                break;
+            }
+        }
+        old_server.send(notification("hola")).await.unwrap();
+        old_server.close(None).await.unwrap();
+        let message = messages.recv().await.unwrap();
+        assert!(matches!(
+            message,
+            GeneralMessage::TwitchMessage(TwitchMessage::PlayerMessage { message_text, .. })
+                if message_text == "hola"
+        ));
+
+        handle.shutdown().await.unwrap();
+        task.await.unwrap();
+        new_server.await.unwrap();
+        while let Ok(message) = messages.try_recv() {
+            assert!(!matches!(
+                message,
+                GeneralMessage::ConnectionStatus(ConnectionStatus::Reconnecting)
+            ));
+        }
     }
 }
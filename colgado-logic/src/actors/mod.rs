@@ -1,5 +1,6 @@
 pub mod game_actor;
 pub mod message_actor;
+pub mod transport;
 
 pub type WebSocket = tokio_tungstenite::WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>;
 pub type WsSender = SplitSink<WebSocket, Message>;
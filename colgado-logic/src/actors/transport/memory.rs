@@ -0,0 +1,67 @@
+use std::sync::{Arc, Mutex};
+
+use super::ChatTransport;
+use crate::errors::TransportError;
+
+/// Message written by the game in a `MemoryTransport`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SentMessage {
+    pub text: String,
+    pub reply_to: Option<String>,
+}
+
+/// Chat kept in memory, for tests, the clones share the messages
+#[derive(Clone, Debug, Default)]
+pub struct MemoryTransport {
+    sent: Arc<Mutex<Vec<SentMessage>>>,
+    subscriptions: Arc<Mutex<Vec<String>>>,
+}
+
+impl MemoryTransport {
+    pub fn sent(&self) -> Vec<SentMessage> {
+        self.sent.lock().expect("Memory chat poisoned").clone()
+    }
+
+    /// Sessions subscribed to the chat
+    pub fn subscriptions(&self) -> Vec<String> {
+        self.subscriptions
+            .lock()
+            .expect("Memory chat poisoned")
+            .clone()
+    }
+
+    fn push(&self, text: &str, reply_to: Option<&str>) {
+        let message = SentMessage {
+            text: text.to_owned(),
+            reply_to: reply_to.map(str::to_owned),
+        };
+        self.sent
+            .lock()
+            .expect("Memory chat poisoned")
+            .push(message);
+    }
+}
+
+impl ChatTransport for MemoryTransport {
+    async fn subscribe(&self, session_id: &str) -> Result<(), TransportError> {
+        let mut subscriptions = self.subscriptions.lock().expect("Memory chat poisoned");
+        subscriptions.push(session_id.to_owned());
+        Ok(())
+    }
+
+    async fn unsubscribe(&self, session_id: &str) -> Result<(), TransportError> {
+        let mut subscriptions = self.subscriptions.lock().expect("Memory chat poisoned");
+        subscriptions.retain(|subscribed| subscribed != session_id);
+        Ok(())
+    }
+
+    async fn send(&self, message: &str) -> Result<(), TransportError> {
+        self.push(message, None);
+        Ok(())
+    }
+
+    async fn reply(&self, message: &str, message_id: &str) -> Result<(), TransportError> {
+        self.push(message, Some(message_id));
+        Ok(())
+    }
+}
@@ -0,0 +1,44 @@
+//! Chat the game is played in, the game actor only talks to it through these traits
+mod memory;
+mod twitch;
+
+use std::future::Future;
+
+pub use memory::{MemoryTransport, SentMessage};
+pub use twitch::TwitchTransport;
+
+use crate::errors::TransportError;
+
+use super::models::messages::GeneralMessage;
+
+/// Outgoing side of the chat
+pub trait ChatTransport: Send + Sync + 'static {
+    /// Subscribes the session to the chat messages of the broadcaster
+    fn subscribe(
+        &self,
+        session_id: &str,
+    ) -> impl Future<Output = Result<(), TransportError>> + Send;
+
+    fn send(&self, message: &str) -> impl Future<Output = Result<(), TransportError>> + Send;
+
+    /// Sends a message as a reply to the message with id `message_id`
+    fn reply(
+        &self,
+        message: &str,
+        message_id: &str,
+    ) -> impl Future<Output = Result<(), TransportError>> + Send;
+
+    /// Removes the chat subscription of the session. By default nothing is done,
+    /// since Twitch drops the subscriptions of a session when its WebSocket is closed
+    fn unsubscribe(
+        &self,
+        _session_id: &str,
+    ) -> impl Future<Output = Result<(), TransportError>> + Send {
+        async { Ok(()) }
+    }
+}
+
+/// Incoming side of the chat, receives every message read from it
+pub trait ChatSink: Send + 'static {
+    fn deliver(&self, message: GeneralMessage);
+}
@@ -0,0 +1,129 @@
+use bytes::Bytes;
+use http_body_util::{BodyExt, Empty};
+use hyper::{
+    header::{AUTHORIZATION, HOST},
+    Method, Request,
+};
+use hyper_util::rt::TokioIo;
+use serde::Deserialize;
+use tokio::net::TcpStream;
+use tokio_native_tls::{native_tls, TlsConnector};
+use trequests::models::info;
+use trequests::models::requests::send_msg_request::SendMsgRequest;
+
+use super::ChatTransport;
+use crate::{announcements::DEFAULT_BOT_NAME, errors::TransportError};
+
+const HELIX_HOST: &str = "api.twitch.tv";
+const SUBSCRIPTIONS_PATH: &str = "/helix/eventsub/subscriptions";
+
+/// Page of `GET /eventsub/subscriptions`, only what is needed to find the ones of a session
+#[derive(Debug, Deserialize)]
+struct Subscriptions {
+    data: Vec<Subscription>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Subscription {
+    id: String,
+    transport: SubscriptionTransport,
+}
+
+#[derive(Debug, Deserialize)]
+struct SubscriptionTransport {
+    /// Missing for the webhook subscriptions
+    session_id: Option<String>,
+}
+
+/// Twitch chat through the Helix API, the messages are received by EventSub
+pub struct TwitchTransport {
+    user_info: info::User,
+    bot_info: info::Bot,
+    /// Name shown on the messages
+    bot_name: String,
+}
+
+impl TwitchTransport {
+    pub fn new(user_info: info::User, bot_info: info::Bot) -> Self {
+        Self {
+            user_info,
+            bot_info,
+            bot_name: DEFAULT_BOT_NAME.to_owned(),
+        }
+    }
+
+    pub fn with_bot_name(mut self, bot_name: String) -> Self {
+        self.bot_name = bot_name;
+        self
+    }
+
+    /// Request to the Helix API with the token of the bot, returns the body of the response
+    async fn helix(&self, method: Method, path: &str) -> Result<Bytes, TransportError> {
+        let request = Request::builder()
+            .method(method)
+            .uri(format!("https://{HELIX_HOST}{path}"))
+            .header(HOST, HELIX_HOST)
+            .header("client-id", &self.bot_info.client_id)
+            .header(AUTHORIZATION, format!("Bearer {}", self.bot_info.token))
+            .body(Empty::<Bytes>::new())?;
+        let stream = TcpStream::connect((HELIX_HOST, 443)).await?;
+        let connector = TlsConnector::from(native_tls::TlsConnector::new()?);
+        let stream = connector.connect(HELIX_HOST, stream).await?;
+        let (mut sender, connection) =
+            hyper::client::conn::http1::handshake(TokioIo::new(stream)).await?;
+        tokio::spawn(async move {
+            if let Err(err) = connection.await {
+                crate::log_error!("[Helix]: {err}");
+            }
+        });
+        let response = sender.send_request(request).await?;
+        if !response.status().is_success() {
+            return Err(TransportError::Status {
+                status: response.status().as_u16(),
+            });
+        }
+        Ok(response.into_body().collect().await?.to_bytes())
+    }
+}
+
+impl ChatTransport for TwitchTransport {
+    async fn subscribe(&self, session_id: &str) -> Result<(), TransportError> {
+        let user_id = &self.user_info.user_id;
+        trequests::subscribe_to_wb(&self.bot_info, session_id, user_id, user_id).await?;
+        Ok(())
+    }
+
+    /// The id of the subscription is not returned when subscribing,
+    /// so the subscriptions of the bot are listed to find the ones of the session
+    async fn unsubscribe(&self, session_id: &str) -> Result<(), TransportError> {
+        let body = self.helix(Method::GET, SUBSCRIPTIONS_PATH).await?;
+        let subscriptions: Subscriptions = serde_json::from_slice(&body)?;
+        let session_subscriptions = subscriptions.data.into_iter().filter(|subscription| {
+            subscription.transport.session_id.as_deref() == Some(session_id)
+        });
+        for subscription in session_subscriptions {
+            let path = format!("{SUBSCRIPTIONS_PATH}?id={}", subscription.id);
+            self.helix(Method::DELETE, &path).await?;
+        }
+        Ok(())
+    }
+
+    async fn send(&self, message: &str) -> Result<(), TransportError> {
+        let user_id = &self.user_info.user_id;
+        SendMsgRequest::new(user_id, user_id, message)
+            .bot_name(&self.bot_name)
+            .send(&self.bot_info)
+            .await?;
+        Ok(())
+    }
+
+    async fn reply(&self, message: &str, message_id: &str) -> Result<(), TransportError> {
+        let user_id = &self.user_info.user_id;
+        SendMsgRequest::new(user_id, user_id, message)
+            .reply_to(message_id)
+            .bot_name(&self.bot_name)
+            .send(&self.bot_info)
+            .await?;
+        Ok(())
+    }
+}
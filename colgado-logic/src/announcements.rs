@@ -0,0 +1,279 @@
+//! Messages sent to the chat during a round, each channel can replace them with its own
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::{self, Catalog};
+
+/// Name shown on the messages of the bot when no other value is configured
+pub const DEFAULT_BOT_NAME: &str = "Colgado";
+
+/// `[announcements]` section of `env.toml`
+///
+/// The templates replace the texts of the chat language, an empty one silences the event.
+/// They can use `{word}`, `{category}`, `{command}`, `{player}`, `{winner}`, `{lives}`,
+/// `{max-lives}` and `{time-left}`
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct AnnouncementsConfig {
+    pub bot_name: String,
+    pub round_start: Option<String>,
+    /// Reply to a guessed letter, silent unless configured
+    pub letter_found: Option<String>,
+    /// Reply to a wrong guess of the whole word
+    pub wrong_guess: Option<String>,
+    pub win: Option<String>,
+    /// The players ran out of lives
+    pub loss: Option<String>,
+    pub timeout: Option<String>,
+}
+
+impl Default for AnnouncementsConfig {
+    fn default() -> Self {
+        Self {
+            bot_name: DEFAULT_BOT_NAME.to_owned(),
+            round_start: None,
+            letter_found: None,
+            wrong_guess: None,
+            win: None,
+            loss: None,
+            timeout: None,
+        }
+    }
+}
+
+/// Events announced in the chat, `[game.announce]` section of `env.toml`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct AnnouncementToggles {
+    pub round_start: bool,
+    pub letter_found: bool,
+    pub wrong_guess: bool,
+    pub win: bool,
+    pub loss: bool,
+    pub timeout: bool,
+}
+
+impl Default for AnnouncementToggles {
+    fn default() -> Self {
+        Self {
+            round_start: true,
+            letter_found: true,
+            wrong_guess: true,
+            win: true,
+            loss: true,
+            timeout: true,
+        }
+    }
+}
+
+impl AnnouncementToggles {
+    pub fn is_enabled(&self, announcement: Announcement) -> bool {
+        match announcement {
+            Announcement::RoundStart => self.round_start,
+            Announcement::LetterFound => self.letter_found,
+            Announcement::WrongGuess => self.wrong_guess,
+            Announcement::Win => self.win,
+            Announcement::Loss => self.loss,
+            Announcement::TimeOut => self.timeout,
+        }
+    }
+
+    pub fn set_enabled(&mut self, announcement: Announcement, enabled: bool) {
+        let toggle = match announcement {
+            Announcement::RoundStart => &mut self.round_start,
+            Announcement::LetterFound => &mut self.letter_found,
+            Announcement::WrongGuess => &mut self.wrong_guess,
+            Announcement::Win => &mut self.win,
+            Announcement::Loss => &mut self.loss,
+            Announcement::TimeOut => &mut self.timeout,
+        };
+        *toggle = enabled;
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Announcement {
+    RoundStart,
+    LetterFound,
+    WrongGuess,
+    Win,
+    Loss,
+    TimeOut,
+}
+
+/// Values of the placeholders, the missing ones are left empty
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Placeholders<'a> {
+    /// Hidden word while playing, the solution once the round is over
+    pub word: &'a str,
+    pub category: Option<&'a str>,
+    pub command: &'a str,
+    /// Player of the guess
+    pub player: Option<&'a str>,
+    pub winner: Option<&'a str>,
+    pub lives: usize,
+    pub max_lives: usize,
+    /// Seconds left in the round, only with the timer
+    pub time_left: Option<u64>,
+}
+
+impl Placeholders<'_> {
+    fn fill(&self, template: &str) -> String {
+        let time_left = self
+            .time_left
+            .map(|seconds| format!("{}:{:02}", seconds / 60, seconds % 60))
+            .unwrap_or_default();
+        let args: [(&str, &dyn Display); 8] = [
+            ("word", &self.word),
+            ("category", &self.category.unwrap_or_default()),
+            ("command", &self.command),
+            ("player", &self.player.unwrap_or_default()),
+            ("winner", &self.winner.unwrap_or_default()),
+            ("lives", &self.lives),
+            ("max-lives", &self.max_lives),
+            ("time-left", &time_left),
+        ];
+        i18n::fill(template, &args)
+    }
+}
+
+/// Builds the announcements from the templates, or from the catalog when missing
+#[derive(Clone, Debug, Default)]
+pub struct Announcer {
+    config: AnnouncementsConfig,
+    catalog: Catalog,
+    toggles: AnnouncementToggles,
+}
+
+impl Announcer {
+    pub fn new(config: AnnouncementsConfig, catalog: Catalog) -> Self {
+        Self {
+            config,
+            catalog,
+            toggles: AnnouncementToggles::default(),
+        }
+    }
+
+    /// Sets the language of the texts used when there is no template
+    pub fn with_catalog(mut self, catalog: Catalog) -> Self {
+        self.catalog = catalog;
+        self
+    }
+
+    /// Sets the events that are announced
+    pub fn with_toggles(mut self, toggles: AnnouncementToggles) -> Self {
+        self.toggles = toggles;
+        self
+    }
+
+    /// Texts of the chat language
+    pub fn catalog(&self) -> Catalog {
+        self.catalog
+    }
+
+    pub fn bot_name(&self) -> &str {
+        &self.config.bot_name
+    }
+
+    /// Message of the event, `None` when it is silenced
+    pub fn announce(&self, announcement: Announcement, values: &Placeholders) -> Option<String> {
+        if !self.toggles.is_enabled(announcement) {
+            return None;
+        }
+        let template = match self.template(announcement) {
+            Some(template) => template.to_owned(),
+            None => self.default_template(announcement, values)?,
+        };
+        if template.trim().is_empty() {
+            return None;
+        }
+        Some(values.fill(&template))
+    }
+
+    fn template(&self, announcement: Announcement) -> Option<&str> {
+        let template = match announcement {
+            Announcement::RoundStart => &self.config.round_start,
+            Announcement::LetterFound => &self.config.letter_found,
+            Announcement::WrongGuess => &self.config.wrong_guess,
+            Announcement::Win => &self.config.win,
+            Announcement::Loss => &self.config.loss,
+            Announcement::TimeOut => &self.config.timeout,
+        };
+        template.as_deref()
+    }
+
+    fn default_template(
+        &self,
+        announcement: Announcement,
+        values: &Placeholders,
+    ) -> Option<String> {
+        let key = match announcement {
+            // the category is only a hint when there is one
+            Announcement::RoundStart if values.category.is_some() => {
+                let start = self.catalog.text("chat.round-start-command");
+                let hint = self.catalog.text("chat.category-hint");
+                return Some(format!("{start}. {hint}"));
+            }
+            Announcement::RoundStart => "chat.round-start-command",
+            Announcement::LetterFound => return None,
+            Announcement::WrongGuess => "chat.wrong-solve",
+            Announcement::Win => "chat.round-won",
+            Announcement::Loss => "chat.round-lost",
+            Announcement::TimeOut => "chat.round-timed-out",
+        };
+        Some(self.catalog.text(key).to_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::Language;
+
+    #[test]
+    fn templates() {
+        let config = AnnouncementsConfig {
+            win: Some(
+                "¡{winner} ha ganado con {lives}/{max-lives} vidas y {time-left}!".to_owned(),
+            ),
+            wrong_guess: Some(String::new()),
+            ..AnnouncementsConfig::default()
+        };
+        let toggles = AnnouncementToggles {
+            timeout: false,
+            ..AnnouncementToggles::default()
+        };
+        let announcer = Announcer::new(config, Catalog::new(Language::En)).with_toggles(toggles);
+        let values = Placeholders {
+            word: "casa",
+            command: "!colgado",
+            winner: Some("ana"),
+            lives: 4,
+            max_lives: 6,
+            time_left: Some(75),
+            ..Placeholders::default()
+        };
+
+        let win = announcer.announce(Announcement::Win, &values);
+        assert_eq!(win.as_deref(), Some("¡ana ha ganado con 4/6 vidas y 1:15!"));
+        assert_eq!(announcer.announce(Announcement::WrongGuess, &values), None);
+        assert_eq!(announcer.announce(Announcement::LetterFound, &values), None);
+        assert_eq!(announcer.announce(Announcement::TimeOut, &values), None);
+        let loss = announcer.announce(Announcement::Loss, &values);
+        assert_eq!(
+            loss.as_deref(),
+            Some("Round lost, you ran out of lives, the word was casa")
+        );
+
+        let values = Placeholders {
+            category: Some("animals"),
+            ..values
+        };
+        let start = announcer
+            .announce(Announcement::RoundStart, &values)
+            .unwrap();
+        assert!(start.contains("!colgado"));
+        assert!(start.ends_with("Hint: the category is animals"));
+    }
+}
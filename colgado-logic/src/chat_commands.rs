@@ -0,0 +1,85 @@
+//! Subcommands of the game command to run the rounds from the chat
+use crate::{i18n::Catalog, models::eventsub::ChatRole};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChatCommand {
+    /// Starts a round with a random word, from the category when given
+    Start(Option<String>),
+    /// Ends the round and starts another one, nothing happens without a round
+    Skip,
+    /// Reveals a letter as a hint
+    Reveal,
+    /// Ends the round as lost
+    End,
+    /// Shows the leaderboard
+    Score,
+}
+
+impl ChatCommand {
+    /// Text written after the game command, like `empezar animales`, with the names of the
+    /// catalog language
+    pub fn parse(text: &str, catalog: Catalog) -> Option<Self> {
+        let text = text.trim();
+        let (name, argument) = match text.split_once(char::is_whitespace) {
+            Some((name, argument)) => (name, Some(argument.trim().to_owned())),
+            None => (text, None),
+        };
+        let name = name.to_lowercase();
+        let is = |key| name == catalog.text(key);
+        if is("command.start") {
+            return Some(Self::Start(argument));
+        }
+        if argument.is_some() {
+            return None;
+        }
+        let command = if is("command.skip") {
+            Self::Skip
+        } else if is("command.reveal") {
+            Self::Reveal
+        } else if is("command.end") {
+            Self::End
+        } else if is("command.score") {
+            Self::Score
+        } else {
+            return None;
+        };
+        Some(command)
+    }
+
+    /// Lowest role allowed to use the command
+    pub fn required_role(&self) -> ChatRole {
+        match self {
+            Self::Score => ChatRole::Viewer,
+            _ => ChatRole::Moderator,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::Language;
+
+    #[test]
+    fn parse_commands() {
+        let spanish = Catalog::new(Language::Es);
+        let start = ChatCommand::parse("empezar  animales", spanish).unwrap();
+        assert_eq!(start, ChatCommand::Start(Some("animales".to_owned())));
+        assert_eq!(
+            ChatCommand::parse("Saltar", spanish),
+            Some(ChatCommand::Skip)
+        );
+        // a guess of two words is not a command
+        assert_eq!(ChatCommand::parse("terminar ya", spanish), None);
+        assert_eq!(ChatCommand::parse("casa", spanish), None);
+        // only the names of the chat language
+        assert_eq!(ChatCommand::parse("score", spanish), None);
+        let english = Catalog::new(Language::En);
+        assert_eq!(
+            ChatCommand::parse("score", english),
+            Some(ChatCommand::Score)
+        );
+        assert_eq!(ChatCommand::parse("puntos", english), None);
+        assert!(ChatCommand::Score.required_role() < ChatCommand::End.required_role());
+    }
+}
@@ -0,0 +1,223 @@
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::{
+    announcements::AnnouncementsConfig,
+    errors::{ColgadoLogicError, InvalidValue},
+    game_config::GameConfig,
+    i18n::Language,
+    mock::MockConfig,
+    overlay::OverlayConfig,
+    settings::Settings,
+    word_bank::WordBankConfig,
+};
+
+/// File with the options of the apps and the game
+pub const CONFIG_FILE: &str = "env.toml";
+
+/// Options of `env.toml` read by the game, the Twitch ones are read by `trequests`
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct Config {
+    /// Twitch app used to log in, also read by `trequests`
+    pub client_id: String,
+    /// Prefix of the whole-word guesses and of the subcommands, also read by `trequests`
+    pub command: String,
+    /// Language of the apps and the chat unless they have their own
+    pub language: Language,
+    /// Rules of the rounds
+    pub game: GameConfig,
+    /// Language of the apps
+    pub ui_language: Option<Language>,
+    /// Language of the messages sent to the chat and of the overlay
+    pub chat_language: Option<Language>,
+    /// Local stand-in for Twitch, used instead of it when present and enabled
+    pub mock: Option<MockConfig>,
+    /// Page for OBS, served when present and enabled
+    pub overlay: Option<OverlayConfig>,
+    /// Categories of random words, an empty bank when the folder does not exist
+    pub words: WordBankConfig,
+    /// Name of the bot and templates of the messages sent to the chat
+    pub announcements: AnnouncementsConfig,
+}
+
+impl Config {
+    pub async fn load(path: impl AsRef<Path>) -> Result<Self, ColgadoLogicError> {
+        let path = path.as_ref();
+        let text = tokio::fs::read_to_string(path)
+            .await
+            .map_err(|err| ColgadoLogicError::ConfigIo { err })?;
+        let config = Self::from_toml(&text).map_err(|err| ColgadoLogicError::ConfigFormat {
+            path: path.to_owned(),
+            err,
+        })?;
+        config
+            .validate()
+            .map_err(|err| ColgadoLogicError::InvalidConfig {
+                path: path.to_owned(),
+                err,
+            })?;
+        Ok(config)
+    }
+
+    /// Checks the values the types allow but the game does not, the first one found is returned
+    pub fn validate(&self) -> Result<(), InvalidValue> {
+        self.game.validate()?;
+        self.words.validate()
+    }
+
+    fn from_toml(text: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(text)
+    }
+
+    /// Options that can be changed from the settings screen
+    pub fn settings(&self) -> Settings {
+        Settings {
+            command: self.command.clone(),
+            client_id: self.client_id.clone(),
+            language: self.language,
+            ui_language: self.ui_language,
+            chat_language: self.chat_language,
+            game: self.game.clone(),
+            overlay: self.overlay.clone(),
+        }
+    }
+
+    pub fn ui_language(&self) -> Language {
+        self.ui_language.unwrap_or(self.language)
+    }
+
+    pub fn chat_language(&self) -> Language {
+        self.chat_language.unwrap_or(self.language)
+    }
+
+    /// Mock options when the mock has to be used
+    pub fn enabled_mock(&self) -> Option<&MockConfig> {
+        self.mock.as_ref().filter(|mock| mock.enabled)
+    }
+
+    pub fn enabled_overlay(&self) -> Option<&OverlayConfig> {
+        self.overlay.as_ref().filter(|overlay| overlay.enabled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{announcements::DEFAULT_BOT_NAME, score::ScoreWeights, word_bank::Difficulty};
+
+    #[test]
+    fn mock_section() {
+        let text = r#"
+            client-id = "abc"
+            redirect-urls = []
+            command = "!colgado"
+
+            [mock]
+            players = 5
+            script = "chat.txt"
+        "#;
+        let config = Config::from_toml(text).unwrap();
+        let mock = config.enabled_mock().unwrap();
+        assert_eq!(mock.players, 5);
+        assert_eq!(mock.eventsub_port, MockConfig::default().eventsub_port);
+
+        let config = Config::from_toml("command = \"!colgado\"").unwrap();
+        assert!(config.enabled_mock().is_none());
+    }
+
+    #[test]
+    fn words_section() {
+        let text = r#"
+            [words]
+            max-length = 8
+            difficulty = "easy"
+        "#;
+        let config = Config::from_toml(text).unwrap();
+        assert_eq!(config.words.path, WordBankConfig::default().path);
+        assert_eq!(config.words.filter().max_length, Some(8));
+        assert_eq!(config.words.difficulty, Some(Difficulty::Easy));
+    }
+
+    #[test]
+    fn languages() {
+        let config = Config::from_toml("language = \"en\"").unwrap();
+        assert_eq!(config.ui_language(), Language::En);
+        assert_eq!(config.chat_language(), Language::En);
+
+        let config = Config::from_toml("chat-language = \"en\"").unwrap();
+        assert_eq!(config.ui_language(), Language::Es);
+        assert_eq!(config.chat_language(), Language::En);
+    }
+
+    #[test]
+    fn announcements_section() {
+        let text = r#"
+            [announcements]
+            bot-name = "Verdugo"
+            win = "{winner} se salva"
+        "#;
+        let config = Config::from_toml(text).unwrap();
+        assert_eq!(config.announcements.bot_name, "Verdugo");
+        assert_eq!(
+            config.announcements.win.as_deref(),
+            Some("{winner} se salva")
+        );
+        assert_eq!(config.announcements.loss, None);
+
+        let config = Config::from_toml("").unwrap();
+        assert_eq!(config.announcements.bot_name, DEFAULT_BOT_NAME);
+    }
+
+    #[test]
+    fn game_section() {
+        let text = r#"
+            [game]
+            lives = 8
+            max-word-length = 12
+
+            [game.normalization]
+            fold-diacritics = false
+
+            [game.scoring]
+            solve = 20
+
+            [game.announce]
+            letter-found = false
+        "#;
+        let config = Config::from_toml(text).unwrap();
+        assert_eq!(config.validate(), Ok(()));
+        assert_eq!(config.game.lives, 8);
+        assert!(!config.game.normalization.fold_diacritics);
+        assert!(config.game.normalization.keep_enie);
+        assert_eq!(config.game.scoring.solve, 20);
+        assert_eq!(config.game.scoring.letter, ScoreWeights::default().letter);
+        assert!(!config.game.announce.letter_found);
+        assert!(config.game.announce.win);
+
+        assert_eq!(Config::from_toml("").unwrap().game, GameConfig::default());
+    }
+
+    #[test]
+    fn invalid_values() {
+        let key = |text: &str| Config::from_toml(text).unwrap().validate().unwrap_err().key;
+        assert_eq!(key("[game]\nlives = 0"), "game.lives");
+        assert_eq!(key("[game.scoring]\nsolve = -1"), "game.scoring.solve");
+        assert_eq!(
+            key("[game.timer]\nround-seconds = 0"),
+            "game.timer.round-seconds"
+        );
+        assert_eq!(
+            key("[game.limits]\nglobal-max-guesses = 0"),
+            "game.limits.global-max-guesses"
+        );
+        assert_eq!(
+            key("[words]\nmin-length = 6\nmax-length = 4"),
+            "words.min-length"
+        );
+        // disabled sections are not checked
+        let text = "[game.timer]\nenabled = false\nround-seconds = 0";
+        assert_eq!(Config::from_toml(text).unwrap().validate(), Ok(()));
+    }
+}
@@ -1,5 +1,9 @@
+use std::path::PathBuf;
+
 use thiserror::Error;
 
+use crate::i18n::Catalog;
+
 #[derive(Error, Debug)]
 pub enum ColgadoLogicError {
     #[error("{}", err)]
@@ -7,17 +11,163 @@ pub enum ColgadoLogicError {
         #[from]
         err: trequests::errors::TRequestsError,
     },
+    #[error("Error connecting to Twitch: {}", err)]
+    ConnectionError {
+        #[from]
+        err: tungstenite::Error,
+    },
+    #[error("Error reading a Twitch message: {}", err)]
+    MessageError {
+        #[from]
+        err: MessageError,
+    },
+    #[error("Error subscribing to the chat: {}", err)]
+    SubscriptionError { err: TransportError },
+    #[error("Error sending a chat message: {}", err)]
+    SendError { err: TransportError },
+    #[error("The game actor has stopped")]
+    ActorStopped,
+    #[error("The actors did not stop in time")]
+    ShutdownTimeout,
+    #[error("Error accessing the stats file: {}", err)]
+    StatsIo {
+        #[from]
+        err: std::io::Error,
+    },
+    #[error("Invalid stats file {}: {}", path.display(), err)]
+    StatsFormat { path: PathBuf, err: toml::de::Error },
+    #[error("Error saving the stats: {}", err)]
+    StatsSerialize { err: toml::ser::Error },
+    #[error("Error reading the config file: {}", err)]
+    ConfigIo { err: std::io::Error },
+    #[error("Invalid config file {}: {}", path.display(), err)]
+    ConfigFormat { path: PathBuf, err: toml::de::Error },
+    #[error("Invalid config file {}: {}", path.display(), err)]
+    ConfigEdit {
+        path: PathBuf,
+        err: toml_edit::TomlError,
+    },
+    #[error("Invalid config file {}: {}", path.display(), err)]
+    InvalidConfig { path: PathBuf, err: InvalidValue },
+    #[error("Error saving the config file {}: {}", path.display(), err)]
+    ConfigSave { path: PathBuf, err: std::io::Error },
+    #[error("Error saving the config: {}", err)]
+    ConfigSerialize { err: toml::ser::Error },
+    #[error("Error starting the mock server: {}", err)]
+    MockServer { err: std::io::Error },
+    #[error("Error starting the overlay server: {}", err)]
+    OverlayServer { err: std::io::Error },
+    #[error("Error reading the words {}: {}", path.display(), err)]
+    WordBankIo { path: PathBuf, err: std::io::Error },
+    #[error("Invalid words file {}: {}", path.display(), err)]
+    WordBankFormat { path: PathBuf, err: toml::de::Error },
+    #[error("There are no words left to pick")]
+    NoWordsLeft,
+    #[error("{}", err)]
+    Game {
+        #[from]
+        err: GameError,
+    },
+}
+
+impl ColgadoLogicError {
+    /// The game can not continue without connecting again
+    pub fn is_fatal(&self) -> bool {
+        matches!(
+            self,
+            Self::ConnectionError { .. } | Self::SubscriptionError { .. } | Self::ActorStopped
+        )
+    }
+}
+
+/// Value of the config that breaks a rule, `key` is its path like `game.lives`
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("{} {}", key, reason)]
+pub struct InvalidValue {
+    pub key: &'static str,
+    pub reason: &'static str,
+}
+
+impl InvalidValue {
+    pub fn new(key: &'static str, reason: &'static str) -> Self {
+        Self { key, reason }
+    }
+}
+
+/// Errors of the requests made to the chat, Twitch or the local mock
+#[derive(Error, Debug)]
+pub enum TransportError {
+    #[error("{}", err)]
+    Twitch {
+        #[from]
+        err: trequests::errors::TRequestsError,
+    },
+    #[error("{}", err)]
+    Http {
+        #[from]
+        err: hyper_util::client::legacy::Error,
+    },
+    #[error("Invalid request: {}", err)]
+    InvalidRequest {
+        #[from]
+        err: hyper::http::Error,
+    },
+    #[error("Error connecting: {}", err)]
+    Connection {
+        #[from]
+        err: std::io::Error,
+    },
+    #[error("Error securing the connection: {}", err)]
+    Tls {
+        #[from]
+        err: tokio_native_tls::native_tls::Error,
+    },
+    #[error("{}", err)]
+    Request {
+        #[from]
+        err: hyper::Error,
+    },
+    #[error("Invalid response: {}", err)]
+    InvalidResponse {
+        #[from]
+        err: serde_json::Error,
+    },
+    #[error("Unexpected response status {}", status)]
+    Status { status: u16 },
+}
+
+/// Errors reading the messages received from Twitch
+#[derive(Error, Debug)]
+pub enum MessageError {
+    #[error("Malformed message: {}", err)]
+    Malformed {
+        #[from]
+        err: serde_json::Error,
+    },
+    #[error("Message {} without {}", message_type, field)]
+    MissingField {
+        message_type: String,
+        field: &'static str,
+    },
+    #[error("Message type not handled: {}", message_type)]
+    UnknownType { message_type: String },
+    #[error("Subscription type not handled: {}", subscription_type)]
+    UnknownSubscription { subscription_type: String },
 }
 
 #[derive(Error, Debug)]
 pub enum GameError {
     #[error("Word too long")]
     InvalidWord,
+    #[error("The word is longer than {} letters", max)]
+    WordTooLong { max: usize },
 }
 impl GameError {
-    pub fn twitch_message_error(&self) -> &'static str {
+    pub fn twitch_message_error(&self, catalog: Catalog) -> &'static str {
         match self {
-            GameError::InvalidWord => "La palabra es demasiado larga",
+            GameError::InvalidWord | GameError::WordTooLong { .. } => {
+                catalog.text("chat.word-too-long")
+            }
         }
     }
 }
@@ -0,0 +1,146 @@
+//! Rules of the rounds, read from the `[game]` section of `env.toml`
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    announcements::AnnouncementToggles,
+    errors::{GameError, InvalidValue},
+    limits::GuessLimitsConfig,
+    score::ScoreWeights,
+    timer::TimerConfig,
+    word::{Game, Normalization, DEFAULT_LIVES, DEFAULT_SOLVE_PENALTY},
+};
+
+/// `[game]` section of `env.toml`, every key has a default
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct GameConfig {
+    /// Failed guesses allowed in a round
+    pub lives: usize,
+    /// Lives lost by a wrong whole-word guess
+    pub solve_penalty: usize,
+    /// Letters of the longest word that can be typed, unlimited when missing
+    pub max_word_length: Option<usize>,
+    pub normalization: Normalization,
+    pub scoring: ScoreWeights,
+    /// Messages sent to the chat, all of them by default
+    pub announce: AnnouncementToggles,
+    /// Time limit of the rounds, used when present and enabled
+    pub timer: Option<TimerConfig>,
+    /// Rules against spamming guesses, used when present and enabled
+    pub limits: Option<GuessLimitsConfig>,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            lives: DEFAULT_LIVES,
+            solve_penalty: DEFAULT_SOLVE_PENALTY,
+            max_word_length: None,
+            normalization: Normalization::default(),
+            scoring: ScoreWeights::default(),
+            announce: AnnouncementToggles::default(),
+            timer: None,
+            limits: None,
+        }
+    }
+}
+
+impl GameConfig {
+    /// Game of the word with these rules
+    pub fn new_game(&self, word: String) -> Game {
+        Game::new(word)
+            .with_lives(self.lives)
+            .with_solve_penalty(self.solve_penalty)
+            .with_normalization(self.normalization)
+    }
+
+    /// Checks the word typed for a round is short enough
+    pub fn check_word(&self, word: &str) -> Result<(), GameError> {
+        match self.max_word_length {
+            Some(max) if Game::count_letters(word) > max => Err(GameError::WordTooLong { max }),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), InvalidValue> {
+        if self.lives == 0 {
+            return Err(InvalidValue::new("game.lives", "must be at least 1"));
+        }
+        if self.max_word_length == Some(0) {
+            return Err(InvalidValue::new(
+                "game.max-word-length",
+                "must be at least 1",
+            ));
+        }
+        if self.scoring.letter < 0 {
+            return Err(InvalidValue::new(
+                "game.scoring.letter",
+                "can not be negative",
+            ));
+        }
+        if self.scoring.solve < 0 {
+            return Err(InvalidValue::new(
+                "game.scoring.solve",
+                "can not be negative",
+            ));
+        }
+        if let Some(timer) = self.enabled_timer() {
+            timer.validate()?;
+        }
+        if let Some(limits) = self.enabled_limits() {
+            limits.validate()?;
+        }
+        Ok(())
+    }
+
+    pub fn enabled_timer(&self) -> Option<&TimerConfig> {
+        self.timer.as_ref().filter(|timer| timer.enabled)
+    }
+
+    pub fn enabled_limits(&self) -> Option<&GuessLimitsConfig> {
+        self.limits.as_ref().filter(|limits| limits.enabled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::word::{GameOutcome, GuessResult};
+
+    #[test]
+    fn rules() {
+        let config = GameConfig {
+            lives: 3,
+            max_word_length: Some(4),
+            ..GameConfig::default()
+        };
+        assert!(config.check_word("casa").is_ok());
+        assert!(config.check_word("a b c d").is_ok());
+        assert!(matches!(
+            config.check_word("perro"),
+            Err(GameError::WordTooLong { max: 4 })
+        ));
+        assert_eq!(config.new_game("casa".to_owned()).max_lives(), 3);
+
+        // a wrong whole word costs the penalty
+        let config = GameConfig { lives: 2, ..config };
+        let mut game = config.new_game("casa".to_owned());
+        let result = game.guess(&Game::split_chars("cosa"));
+        assert!(matches!(result, Ok(GuessResult::WrongSolve)));
+        assert_eq!(game.outcome(), GameOutcome::Lost);
+
+        let config = GameConfig { lives: 0, ..config };
+        assert_eq!(config.validate().unwrap_err().key, "game.lives");
+    }
+
+    #[test]
+    fn unknown_keys() {
+        assert!(toml::from_str::<GameConfig>("lives = 3").is_ok());
+        assert!(toml::from_str::<GameConfig>("live = 3").is_err());
+        assert!(toml::from_str::<GameConfig>("[scoring]\nletters = 2").is_err());
+        assert!(toml::from_str::<GameConfig>("[normalization]\nfold = true").is_err());
+        assert!(toml::from_str::<GameConfig>("[announce]\nwins = true").is_err());
+        assert!(toml::from_str::<GameConfig>("[timer]\nround-secs = 60").is_err());
+        assert!(toml::from_str::<GameConfig>("[limits]\ncooldown-ms = 100").is_err());
+    }
+}
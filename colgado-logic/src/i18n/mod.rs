@@ -0,0 +1,135 @@
+//! Texts of the apps and the chat, one catalog per language embedded in the binary
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+    sync::LazyLock,
+};
+
+use serde::{Deserialize, Serialize};
+
+type Messages = HashMap<String, String>;
+
+static SPANISH: LazyLock<Messages> = LazyLock::new(|| parse(include_str!("es.toml")));
+static ENGLISH: LazyLock<Messages> = LazyLock::new(|| parse(include_str!("en.toml")));
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Language {
+    #[default]
+    Es,
+    En,
+}
+
+/// Texts of a language, the keys are `section.name` like `chat.wrong-solve`
+#[derive(Clone, Copy, Debug)]
+pub struct Catalog {
+    language: Language,
+    messages: &'static Messages,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::Es, Language::En];
+
+    /// Code of the language, as used by HTML
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::Es => "es",
+            Language::En => "en",
+        }
+    }
+}
+
+/// Name of the language in itself, as shown in the settings
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Language::Es => write!(f, "Español"),
+            Language::En => write!(f, "English"),
+        }
+    }
+}
+
+impl Catalog {
+    pub fn new(language: Language) -> Self {
+        let messages = match language {
+            Language::Es => &SPANISH,
+            Language::En => &ENGLISH,
+        };
+        Self {
+            language,
+            messages: LazyLock::force(messages),
+        }
+    }
+
+    pub fn language(&self) -> Language {
+        self.language
+    }
+
+    /// Missing texts fall back to Spanish, and to the key itself after that
+    pub fn text(&self, key: &'static str) -> &'static str {
+        self.messages
+            .get(key)
+            .or_else(|| SPANISH.get(key))
+            .map_or(key, String::as_str)
+    }
+
+    /// Text with every `{name}` replaced by its value
+    pub fn format(&self, key: &'static str, args: &[(&str, &dyn Display)]) -> String {
+        fill(self.text(key), args)
+    }
+}
+
+/// Replaces every `{name}` of the text by its value, unknown names are left as they are
+pub fn fill(text: &str, args: &[(&str, &dyn Display)]) -> String {
+    let mut text = text.to_owned();
+    for (name, value) in args {
+        text = text.replace(&format!("{{{name}}}"), &value.to_string());
+    }
+    text
+}
+
+impl Default for Catalog {
+    fn default() -> Self {
+        Self::new(Language::default())
+    }
+}
+
+/// The catalogs are part of the binary, an invalid one is a bug caught by the tests
+fn parse(source: &str) -> Messages {
+    let sections: HashMap<String, Messages> =
+        toml::from_str(source).expect("invalid embedded catalog");
+    sections
+        .into_iter()
+        .flat_map(|(section, messages)| {
+            messages
+                .into_iter()
+                .map(move |(name, text)| (format!("{section}.{name}"), text))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn catalogs_have_the_same_keys() {
+        let mut spanish: Vec<&String> = SPANISH.keys().collect();
+        let mut english: Vec<&String> = ENGLISH.keys().collect();
+        spanish.sort();
+        english.sort();
+        assert_eq!(spanish, english);
+    }
+
+    #[test]
+    fn format_texts() {
+        let catalog = Catalog::new(Language::En);
+        let text = catalog.format("ui.lives", &[("lives", &2), ("max-lives", &6)]);
+        assert_eq!(text, "Lives: 2/6");
+        assert_eq!(
+            Catalog::default().text("chat.wrong-solve"),
+            "Esa no es la palabra"
+        );
+        assert_eq!(catalog.text("ui.missing"), "ui.missing");
+    }
+}
@@ -1,15 +1,41 @@
 pub mod actors;
+pub mod announcements;
+mod chat_commands;
+pub mod config;
 pub mod errors;
+pub mod game_config;
+pub mod i18n;
+pub mod limits;
+pub mod log;
+pub mod mock;
 pub mod models;
+pub mod overlay;
+mod retry;
+pub mod score;
+pub mod settings;
+pub mod stats;
+#[cfg(test)]
+mod test_dir;
+pub mod timer;
 mod word;
+pub mod word_bank;
 
 use std::sync::Arc;
 
-use crate::actors::game_actor::TwitchGameHandle;
+use crate::actors::game_actor::{GameSetup, TwitchGameHandle};
 use crate::actors::message_actor::TwitchMessageHandle;
+use crate::actors::transport::TwitchTransport;
 
+use announcements::Announcer;
+
+use config::{Config, CONFIG_FILE};
 use errors::ColgadoLogicError;
+use i18n::Catalog;
+use mock::MockTwitch;
 use models::handles::Handles;
+use overlay::OverlayServer;
+use retry::RetryPolicy;
+use stats::StatsStore;
 use tokio::task::JoinHandle;
 use tokio_tungstenite::connect_async;
 use trequests::{
@@ -17,11 +43,13 @@ use trequests::{
     models::{file_variables::FileVariables, scope::Scope},
     open_file, URL,
 };
+use word_bank::WordBank;
 
-const FILE: &str = "env.toml";
+const STATS_FILE: &str = "stats.toml";
 
-pub async fn init_flow() -> Result<(Handles, Arc<[JoinHandle<()>]>, Box<str>), ColgadoLogicError> {
-    let file_variables = open_file(FILE)
+pub async fn init_flow() -> Result<(Handles, Arc<[JoinHandle<()>]>), ColgadoLogicError> {
+    let config = Config::load(CONFIG_FILE).await?;
+    let file_variables = open_file(CONFIG_FILE)
         .await
         .map_err(|err| TRequestsError::VarError { err })?;
 
@@ -31,25 +59,58 @@ pub async fn init_flow() -> Result<(Handles, Arc<[JoinHandle<()>]>, Box<str>), C
         ..
     } = file_variables;
 
-    let scopes = &[Scope::UserReadChat, Scope::UserWriteChat];
+    let chat_catalog = Catalog::new(config.chat_language());
+    let announcer = Announcer::new(config.announcements.clone(), chat_catalog)
+        .with_toggles(config.game.announce);
+    let bot_name = announcer.bot_name().to_owned();
+    let setup = GameSetup {
+        command: file_variables.command,
+        stats: StatsStore::load(STATS_FILE).await?,
+        words: WordBank::load(&config.words).await?,
+        rules: config.game.clone(),
+        announcer,
+    };
 
-    let (user, bot_info) = trequests::get_token(client_id, &redirect_urls, scopes).await?;
-    let command = file_variables.command;
+    let (twitch_game_handle, mut tasks, url) = match config.enabled_mock() {
+        Some(mock_config) => {
+            let mock = MockTwitch::bind(mock_config.clone()).await?;
+            crate::log!("Using the local mock instead of Twitch");
+            let url = mock.eventsub_url().to_owned();
+            let (handle, task) = TwitchGameHandle::new_and_joinhandle(mock.transport(), setup);
+            let mut tasks = mock.spawn(handle.watch_game_state()).await?;
+            tasks.push(task);
+            (handle, tasks, url)
+        }
+        None => {
+            let scopes = &[Scope::UserReadChat, Scope::UserWriteChat];
+            let (user_info, bot_info) =
+                trequests::get_token(client_id, &redirect_urls, scopes).await?;
+            let transport = TwitchTransport::new(user_info, bot_info).with_bot_name(bot_name);
+            let (handle, task) = TwitchGameHandle::new_and_joinhandle(transport, setup);
+            (handle, vec![task], URL.to_owned())
+        }
+    };
 
-    let (twitch_game_handle, twitch_game_task) =
-        TwitchGameHandle::new_and_joinhandle(user, bot_info, command.clone());
+    if let Some(overlay_config) = config.enabled_overlay() {
+        let overlay = OverlayServer::bind(overlay_config.clone())
+            .await?
+            .with_catalog(chat_catalog);
+        crate::log!("Overlay available at {}", overlay.url());
+        tasks.extend(overlay.spawn(&twitch_game_handle).await);
+    }
 
-    let (ws_stream, _) = connect_async(URL).await.expect("Failed to connect");
-    println!("WebSocket handshake has been successfully completed");
+    let (ws_stream, _) = RetryPolicy::default()
+        .run("Connection", || connect_async(&url))
+        .await?;
+    crate::log!("WebSocket handshake has been successfully completed");
 
     let (twitch_message_handle, twitch_message_task) =
-        TwitchMessageHandle::new_and_joinhandle(ws_stream, twitch_game_handle.clone());
+        TwitchMessageHandle::new_and_joinhandle(ws_stream, url, twitch_game_handle.clone());
+    tasks.push(twitch_message_task);
 
     let handles = Handles {
         message_handle: twitch_message_handle,
         game_handle: twitch_game_handle,
     };
-    let tasks = vec![twitch_game_task, twitch_message_task];
-    let command: Box<str> = command.into();
-    Ok((handles, tasks.into(), command))
+    Ok((handles, tasks.into()))
 }
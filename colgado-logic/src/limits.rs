@@ -0,0 +1,273 @@
+//! Rules against chatters brute-forcing the alphabet, checked before every guess
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+
+use crate::{errors::InvalidValue, i18n::Catalog};
+
+/// `[game.limits]` section of `env.toml`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct GuessLimitsConfig {
+    pub enabled: bool,
+    /// Time a player waits between guesses, 0 disables it
+    pub player_cooldown_ms: u64,
+    /// Guesses of a player in a round, unlimited when missing
+    pub max_guesses_per_round: Option<usize>,
+    /// Guesses of the whole chat inside `global-window-ms`, unlimited when missing
+    pub global_max_guesses: Option<usize>,
+    pub global_window_ms: u64,
+    /// Reply to the ignored guesses explaining why, once per cooldown and once per round
+    pub replies: bool,
+}
+
+impl Default for GuessLimitsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            player_cooldown_ms: 3000,
+            max_guesses_per_round: None,
+            global_max_guesses: None,
+            global_window_ms: 1000,
+            replies: false,
+        }
+    }
+}
+
+impl GuessLimitsConfig {
+    pub fn validate(&self) -> Result<(), InvalidValue> {
+        if self.max_guesses_per_round == Some(0) {
+            return Err(InvalidValue::new(
+                "game.limits.max-guesses-per-round",
+                "must be at least 1",
+            ));
+        }
+        if self.global_max_guesses == Some(0) {
+            return Err(InvalidValue::new(
+                "game.limits.global-max-guesses",
+                "must be at least 1",
+            ));
+        }
+        if self.global_max_guesses.is_some() && self.global_window_ms == 0 {
+            return Err(InvalidValue::new(
+                "game.limits.global-window-ms",
+                "must be greater than 0",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Why a guess was ignored
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rejection {
+    /// Time left until the player can guess again
+    Cooldown(Duration),
+    RoundLimit,
+    RateLimit,
+}
+
+impl Rejection {
+    pub fn twitch_message(&self, catalog: Catalog) -> String {
+        match self {
+            Rejection::Cooldown(wait) => {
+                let seconds = wait.as_millis().div_ceil(1000);
+                catalog.format("chat.cooldown", &[("seconds", &seconds)])
+            }
+            Rejection::RoundLimit => catalog.text("chat.round-limit").to_owned(),
+            Rejection::RateLimit => catalog.text("chat.rate-limit").to_owned(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct GuessLimiter {
+    config: GuessLimitsConfig,
+    last_guesses: HashMap<String, Instant>,
+    round_guesses: HashMap<String, usize>,
+    /// Guesses of the chat inside the global window, the oldest first
+    recent_guesses: VecDeque<Instant>,
+    /// Last guess of each player whose cooldown was already replied to
+    cooldown_replies: HashMap<String, Instant>,
+    /// Players already told about the round limit this round
+    round_limit_replies: HashSet<String>,
+    /// Players already told about the rate limit this round
+    rate_limit_replies: HashSet<String>,
+}
+
+impl GuessLimiter {
+    pub fn new(config: GuessLimitsConfig) -> Self {
+        Self {
+            config,
+            last_guesses: HashMap::new(),
+            round_guesses: HashMap::new(),
+            recent_guesses: VecDeque::new(),
+            cooldown_replies: HashMap::new(),
+            round_limit_replies: HashSet::new(),
+            rate_limit_replies: HashSet::new(),
+        }
+    }
+
+    /// Whether to reply to the rejection, a player gets one reply for each cooldown
+    /// and one for each of the round and rate limits in each round
+    pub fn should_reply(&mut self, player_id: &str, rejection: Rejection) -> bool {
+        if !self.config.replies {
+            return false;
+        }
+        match rejection {
+            Rejection::Cooldown(_) => {
+                let Some(last_guess) = self.last_guesses.get(player_id).copied() else {
+                    return false;
+                };
+                self.cooldown_replies
+                    .insert(player_id.to_owned(), last_guess)
+                    != Some(last_guess)
+            }
+            Rejection::RoundLimit => self.round_limit_replies.insert(player_id.to_owned()),
+            Rejection::RateLimit => self.rate_limit_replies.insert(player_id.to_owned()),
+        }
+    }
+
+    /// Forgets the guesses counted for the round
+    pub fn new_round(&mut self) {
+        self.round_guesses.clear();
+        self.round_limit_replies.clear();
+        self.rate_limit_replies.clear();
+    }
+
+    /// Counts the guess when it is allowed
+    pub fn check(&mut self, player_id: &str, now: Instant) -> Result<(), Rejection> {
+        let round_guesses = self.round_guesses.get(player_id).copied().unwrap_or(0);
+        if self
+            .config
+            .max_guesses_per_round
+            .is_some_and(|max| round_guesses >= max)
+        {
+            return Err(Rejection::RoundLimit);
+        }
+
+        let cooldown = Duration::from_millis(self.config.player_cooldown_ms);
+        if let Some(last_guess) = self.last_guesses.get(player_id) {
+            let elapsed = now.saturating_duration_since(*last_guess);
+            if elapsed < cooldown {
+                return Err(Rejection::Cooldown(cooldown - elapsed));
+            }
+        }
+
+        let window = Duration::from_millis(self.config.global_window_ms);
+        while self
+            .recent_guesses
+            .front()
+            .is_some_and(|guess| now.saturating_duration_since(*guess) >= window)
+        {
+            self.recent_guesses.pop_front();
+        }
+        if let Some(max) = self.config.global_max_guesses {
+            if self.recent_guesses.len() >= max {
+                return Err(Rejection::RateLimit);
+            }
+            self.recent_guesses.push_back(now);
+        }
+
+        self.last_guesses.insert(player_id.to_owned(), now);
+        self.round_guesses
+            .insert(player_id.to_owned(), round_guesses + 1);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limits() {
+        let config = GuessLimitsConfig {
+            player_cooldown_ms: 2000,
+            max_guesses_per_round: Some(2),
+            global_max_guesses: Some(2),
+            global_window_ms: 1000,
+            ..GuessLimitsConfig::default()
+        };
+        let mut limiter = GuessLimiter::new(config);
+        let start = Instant::now();
+        let at = |millis| start + Duration::from_millis(millis);
+
+        assert_eq!(limiter.check("ana", at(0)), Ok(()));
+        assert_eq!(
+            limiter.check("ana", at(500)),
+            Err(Rejection::Cooldown(Duration::from_millis(1500)))
+        );
+        assert_eq!(limiter.check("luis", at(600)), Ok(()));
+        assert_eq!(limiter.check("eva", at(700)), Err(Rejection::RateLimit));
+        assert_eq!(limiter.check("eva", at(1000)), Ok(()));
+
+        assert_eq!(limiter.check("ana", at(3000)), Ok(()));
+        assert_eq!(limiter.check("ana", at(6000)), Err(Rejection::RoundLimit));
+        limiter.new_round();
+        assert_eq!(limiter.check("ana", at(6000)), Ok(()));
+    }
+
+    #[test]
+    fn replies_once() {
+        let config = GuessLimitsConfig {
+            player_cooldown_ms: 2000,
+            max_guesses_per_round: Some(3),
+            replies: true,
+            ..GuessLimitsConfig::default()
+        };
+        let mut limiter = GuessLimiter::new(config);
+        let start = Instant::now();
+        let at = |millis| start + Duration::from_millis(millis);
+        let reply = |limiter: &mut GuessLimiter, millis| {
+            let rejection = limiter.check("ana", at(millis)).unwrap_err();
+            limiter.should_reply("ana", rejection)
+        };
+
+        assert_eq!(limiter.check("ana", at(0)), Ok(()));
+        assert!(reply(&mut limiter, 500));
+        assert!(!reply(&mut limiter, 1000));
+        // the next cooldown is replied to again
+        assert_eq!(limiter.check("ana", at(2000)), Ok(()));
+        assert!(reply(&mut limiter, 2500));
+        assert!(!reply(&mut limiter, 3000));
+
+        assert_eq!(limiter.check("ana", at(4000)), Ok(()));
+        assert!(reply(&mut limiter, 6000));
+        assert!(!reply(&mut limiter, 7000));
+        limiter.new_round();
+        assert_eq!(limiter.check("ana", at(8000)), Ok(()));
+        assert_eq!(limiter.check("ana", at(10000)), Ok(()));
+        assert_eq!(limiter.check("ana", at(12000)), Ok(()));
+        assert!(reply(&mut limiter, 14000));
+    }
+
+    #[test]
+    fn replies_once_for_each_limit() {
+        let config = GuessLimitsConfig {
+            player_cooldown_ms: 0,
+            max_guesses_per_round: Some(1),
+            global_max_guesses: Some(1),
+            global_window_ms: 1000,
+            replies: true,
+            ..GuessLimitsConfig::default()
+        };
+        let mut limiter = GuessLimiter::new(config);
+        let start = Instant::now();
+        let at = |millis| start + Duration::from_millis(millis);
+
+        assert_eq!(limiter.check("luis", at(0)), Ok(()));
+        assert_eq!(limiter.check("ana", at(100)), Err(Rejection::RateLimit));
+        assert!(limiter.should_reply("ana", Rejection::RateLimit));
+        assert_eq!(limiter.check("ana", at(1000)), Ok(()));
+        assert_eq!(limiter.check("ana", at(2000)), Err(Rejection::RoundLimit));
+        // the rate limit reply does not take the one of the round limit
+        assert!(limiter.should_reply("ana", Rejection::RoundLimit));
+        assert!(!limiter.should_reply("ana", Rejection::RoundLimit));
+        assert!(!limiter.should_reply("ana", Rejection::RateLimit));
+    }
+}
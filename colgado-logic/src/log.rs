@@ -0,0 +1,71 @@
+//! Lines written by the actors and the servers, printed unless an app shows them itself
+use std::sync::Mutex;
+
+use tokio::sync::mpsc;
+
+static SINK: Mutex<Option<mpsc::UnboundedSender<String>>> = Mutex::new(None);
+
+/// Sends the lines to the receiver instead of printing them, until it is dropped.
+/// Used by the terminal app, where printing would break the screen
+pub fn capture() -> mpsc::UnboundedReceiver<String> {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    if let Ok(mut sink) = SINK.lock() {
+        *sink = Some(sender);
+    }
+    receiver
+}
+
+/// Gives the line to the receiver of `capture`, the line is sent back without one
+pub fn send(line: String) -> Result<(), String> {
+    let Ok(mut sink) = SINK.lock() else {
+        return Err(line);
+    };
+    let Some(sender) = sink.as_ref() else {
+        return Err(line);
+    };
+    sender.send(line).map_err(|err| {
+        *sink = None;
+        err.0
+    })
+}
+
+/// Prints to stdout like `println!` unless the lines are captured
+#[macro_export]
+macro_rules! log {
+    ($($arg:tt)*) => {
+        if let Err(line) = $crate::log::send(format!($($arg)*)) {
+            println!("{line}");
+        }
+    };
+}
+
+/// Prints to stderr like `eprintln!` unless the lines are captured
+#[macro_export]
+macro_rules! log_error {
+    ($($arg:tt)*) => {
+        if let Err(line) = $crate::log::send(format!($($arg)*)) {
+            eprintln!("{line}");
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn captured_lines() {
+        let mut receiver = capture();
+        crate::log!("[test]: {}", 1);
+        crate::log_error!("[test]: {}", 2);
+        // the other tests can log at the same time
+        let mut lines = Vec::new();
+        while let Ok(line) = receiver.try_recv() {
+            lines.extend(line.strip_prefix("[test]: ").map(str::to_owned));
+        }
+        assert_eq!(lines, ["1", "2"]);
+
+        drop(receiver);
+        assert_eq!(send("[test]: 3".to_owned()), Err("[test]: 3".to_owned()));
+    }
+}
@@ -0,0 +1,158 @@
+//! EventSub WebSocket server, sends the same messages as Twitch for the chat subscription
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::broadcast::{self, error::RecvError},
+    time::{interval_at, Instant},
+};
+use tokio_tungstenite::{accept_async, tungstenite::Message};
+
+use super::{random_id, simulator::ChatLine, Sessions, MOCK_BROADCASTER_ID, MOCK_BROADCASTER_NAME};
+
+/// The game does not read the timestamps
+const TIMESTAMP: &str = "1970-01-01T00:00:00Z";
+
+pub async fn serve(
+    listener: TcpListener,
+    sessions: Sessions,
+    chat: broadcast::Sender<ChatLine>,
+    keepalive: Duration,
+) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                let session = session(stream, sessions.clone(), chat.subscribe(), keepalive);
+                tokio::spawn(session);
+            }
+            Err(err) => crate::log_error!("[mock EventSub]: {err}"),
+        }
+    }
+}
+
+async fn session(
+    stream: TcpStream,
+    sessions: Sessions,
+    mut chat: broadcast::Receiver<ChatLine>,
+    keepalive: Duration,
+) {
+    let ws_stream = match accept_async(stream).await {
+        Ok(ws_stream) => ws_stream,
+        Err(err) => {
+            crate::log_error!("[mock EventSub]: {err}");
+            return;
+        }
+    };
+    let (mut sender, mut receiver) = ws_stream.split();
+    let session_id = random_id();
+    sessions.open(&session_id);
+
+    let welcome = welcome_message(&session_id, keepalive);
+    let mut keepalive_interval = interval_at(Instant::now() + keepalive, keepalive);
+    let mut next_message = Some(welcome);
+    loop {
+        if let Some(message) = next_message.take() {
+            if sender
+                .send(Message::text(message.to_string()))
+                .await
+                .is_err()
+            {
+                break;
+            }
+            keepalive_interval.reset();
+        }
+        tokio::select! {
+            _ = keepalive_interval.tick() => next_message = Some(metadata_only("session_keepalive")),
+            line = chat.recv() => match line {
+                Ok(line) => {
+                    next_message = sessions
+                        .subscription(&session_id)
+                        .map(|subscription_id| notification_message(&session_id, &subscription_id, &line));
+                }
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            },
+            message = receiver.next() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+    sessions.close(&session_id);
+    let _ = sender.close().await;
+}
+
+fn metadata(message_type: &str) -> Value {
+    json!({
+        "message_id": random_id(),
+        "message_type": message_type,
+        "message_timestamp": TIMESTAMP,
+    })
+}
+
+fn metadata_only(message_type: &str) -> Value {
+    json!({ "metadata": metadata(message_type), "payload": {} })
+}
+
+fn welcome_message(session_id: &str, keepalive: Duration) -> Value {
+    json!({
+        "metadata": metadata("session_welcome"),
+        "payload": {
+            "session": {
+                "id": session_id,
+                "status": "connected",
+                "connected_at": TIMESTAMP,
+                "keepalive_timeout_seconds": keepalive.as_secs(),
+                "reconnect_url": null,
+            }
+        }
+    })
+}
+
+fn notification_message(session_id: &str, subscription_id: &str, line: &ChatLine) -> Value {
+    let mut metadata = metadata("notification");
+    metadata["subscription_type"] = json!("channel.chat.message");
+    metadata["subscription_version"] = json!("1");
+    // the streamer can use the moderator commands from the script
+    let badges = if line.player_name.eq_ignore_ascii_case(MOCK_BROADCASTER_NAME) {
+        json!([{ "set_id": "broadcaster", "id": "1", "info": "" }])
+    } else {
+        json!([])
+    };
+    json!({
+        "metadata": metadata,
+        "payload": {
+            "subscription": {
+                "id": subscription_id,
+                "status": "enabled",
+                "type": "channel.chat.message",
+                "version": "1",
+                "condition": {
+                    "broadcaster_user_id": MOCK_BROADCASTER_ID,
+                    "user_id": MOCK_BROADCASTER_ID,
+                },
+                "transport": { "method": "websocket", "session_id": session_id },
+                "created_at": TIMESTAMP,
+                "cost": 0,
+            },
+            "event": {
+                "broadcaster_user_id": MOCK_BROADCASTER_ID,
+                "broadcaster_user_login": MOCK_BROADCASTER_NAME,
+                "broadcaster_user_name": MOCK_BROADCASTER_NAME,
+                "chatter_user_id": line.player_id,
+                "chatter_user_login": line.player_name.to_lowercase(),
+                "chatter_user_name": line.player_name,
+                "message_id": random_id(),
+                "message": {
+                    "text": line.text,
+                    "fragments": [{ "type": "text", "text": line.text }],
+                },
+                "color": "",
+                "badges": badges,
+                "message_type": "text",
+            }
+        }
+    })
+}
@@ -0,0 +1,201 @@
+//! Fake Helix API with the two endpoints used by the game: chat subscriptions and chat messages
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full};
+use hyper::{
+    body::Incoming, server::conn::http1, service::service_fn, Method, Request, Response, StatusCode,
+};
+use hyper_util::{
+    client::legacy::{connect::HttpConnector, Client},
+    rt::{TokioExecutor, TokioIo},
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::{net::TcpListener, sync::broadcast};
+
+use super::{random_id, simulator::ChatLine, Sessions, MOCK_BROADCASTER_NAME};
+use crate::{actors::transport::ChatTransport, errors::TransportError};
+
+const SUBSCRIPTIONS_PATH: &str = "/helix/eventsub/subscriptions";
+const CHAT_MESSAGES_PATH: &str = "/helix/chat/messages";
+
+#[derive(Debug, Deserialize)]
+struct SubscriptionRequest {
+    #[serde(rename = "type")]
+    subscription_type: String,
+    version: String,
+    condition: Value,
+    transport: Transport,
+}
+
+#[derive(Debug, Deserialize)]
+struct Transport {
+    session_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatMessageRequest {
+    sender_id: String,
+    message: String,
+}
+
+pub async fn serve(listener: TcpListener, sessions: Sessions, chat: broadcast::Sender<ChatLine>) {
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(err) => {
+                crate::log_error!("[mock Helix]: {err}");
+                continue;
+            }
+        };
+        let sessions = sessions.clone();
+        let chat = chat.clone();
+        let service = service_fn(move |request| {
+            let sessions = sessions.clone();
+            let chat = chat.clone();
+            async move { Ok::<_, hyper::Error>(handle(request, &sessions, &chat).await) }
+        });
+        tokio::spawn(async move {
+            let connection = http1::Builder::new().serve_connection(TokioIo::new(stream), service);
+            if let Err(err) = connection.await {
+                crate::log_error!("[mock Helix]: {err}");
+            }
+        });
+    }
+}
+
+async fn handle(
+    request: Request<Incoming>,
+    sessions: &Sessions,
+    chat: &broadcast::Sender<ChatLine>,
+) -> Response<Full<Bytes>> {
+    let route = (request.method().clone(), request.uri().path().to_owned());
+    let body = match request.into_body().collect().await {
+        Ok(body) => body.to_bytes(),
+        Err(_) => return response(StatusCode::BAD_REQUEST, json!({})),
+    };
+    match (route.0, route.1.as_str()) {
+        (Method::POST, SUBSCRIPTIONS_PATH) => match serde_json::from_slice(&body) {
+            Ok(request) => subscribe(request, sessions),
+            Err(err) => bad_request(err),
+        },
+        (Method::POST, CHAT_MESSAGES_PATH) => match serde_json::from_slice(&body) {
+            Ok(request) => send_message(request, chat),
+            Err(err) => bad_request(err),
+        },
+        _ => response(StatusCode::NOT_FOUND, json!({ "error": "Not Found" })),
+    }
+}
+
+fn subscribe(request: SubscriptionRequest, sessions: &Sessions) -> Response<Full<Bytes>> {
+    let session_id = &request.transport.session_id;
+    let Some(id) = sessions.subscribe(session_id) else {
+        return bad_request(format!("session {session_id} does not exist"));
+    };
+    let data = json!({
+        "id": id,
+        "status": "enabled",
+        "type": request.subscription_type,
+        "version": request.version,
+        "condition": request.condition,
+        "transport": { "method": "websocket", "session_id": session_id },
+        "cost": 0,
+    });
+    response(StatusCode::ACCEPTED, json!({ "data": [data], "total": 1 }))
+}
+
+/// The message is written in the chat as sent by the bot, like Twitch does
+fn send_message(
+    request: ChatMessageRequest,
+    chat: &broadcast::Sender<ChatLine>,
+) -> Response<Full<Bytes>> {
+    crate::log!("[mock chat] {MOCK_BROADCASTER_NAME}: {}", request.message);
+    let line = ChatLine {
+        player_id: request.sender_id,
+        player_name: MOCK_BROADCASTER_NAME.to_owned(),
+        text: request.message,
+    };
+    let _ = chat.send(line);
+    let data = json!({ "message_id": random_id(), "is_sent": true });
+    response(StatusCode::OK, json!({ "data": [data] }))
+}
+
+fn bad_request(err: impl ToString) -> Response<Full<Bytes>> {
+    let body = json!({ "error": "Bad Request", "status": 400, "message": err.to_string() });
+    response(StatusCode::BAD_REQUEST, body)
+}
+
+fn response(status: StatusCode, body: Value) -> Response<Full<Bytes>> {
+    let mut response = Response::new(Full::new(Bytes::from(body.to_string())));
+    *response.status_mut() = status;
+    response
+}
+
+/// Client of the fake Helix API, writes in the chat of `broadcaster_id`
+#[derive(Clone, Debug)]
+pub struct HelixClient {
+    base_url: String,
+    broadcaster_id: String,
+    client: Client<HttpConnector, Full<Bytes>>,
+}
+
+impl HelixClient {
+    pub fn new(base_url: String, broadcaster_id: String) -> Self {
+        let client = Client::builder(TokioExecutor::new()).build_http();
+        Self {
+            base_url,
+            broadcaster_id,
+            client,
+        }
+    }
+
+    async fn send_message(
+        &self,
+        message: &str,
+        reply_to: Option<&str>,
+    ) -> Result<(), TransportError> {
+        let body = json!({
+            "broadcaster_id": self.broadcaster_id,
+            "sender_id": self.broadcaster_id,
+            "message": message,
+            "reply_parent_message_id": reply_to,
+        });
+        self.post("/chat/messages", body).await
+    }
+
+    async fn post(&self, path: &str, body: Value) -> Result<(), TransportError> {
+        let request = Request::post(format!("{}{path}", self.base_url))
+            .header("content-type", "application/json")
+            .body(Full::new(Bytes::from(body.to_string())))?;
+        let response = self.client.request(request).await?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(TransportError::Status {
+                status: response.status().as_u16(),
+            })
+        }
+    }
+}
+
+impl ChatTransport for HelixClient {
+    async fn subscribe(&self, session_id: &str) -> Result<(), TransportError> {
+        let body = json!({
+            "type": "channel.chat.message",
+            "version": "1",
+            "condition": {
+                "broadcaster_user_id": self.broadcaster_id,
+                "user_id": self.broadcaster_id,
+            },
+            "transport": { "method": "websocket", "session_id": session_id },
+        });
+        self.post("/eventsub/subscriptions", body).await
+    }
+
+    async fn send(&self, message: &str) -> Result<(), TransportError> {
+        self.send_message(message, None).await
+    }
+
+    async fn reply(&self, message: &str, message_id: &str) -> Result<(), TransportError> {
+        self.send_message(message, Some(message_id)).await
+    }
+}
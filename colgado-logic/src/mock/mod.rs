@@ -0,0 +1,270 @@
+//! Local stand-in for Twitch: an EventSub WebSocket server, a fake Helix API and a chat
+//! simulator, so the game can be played without a Twitch account
+pub mod eventsub;
+pub mod helix;
+pub mod simulator;
+
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use serde::Deserialize;
+use tokio::{
+    net::TcpListener,
+    sync::{broadcast, watch},
+    task::JoinHandle,
+};
+
+use crate::{errors::ColgadoLogicError, models::game_view::GameView};
+use helix::HelixClient;
+use simulator::{ChatLine, ChatSource};
+
+pub const MOCK_BROADCASTER_ID: &str = "12826";
+pub const MOCK_BROADCASTER_NAME: &str = "colgado";
+const CHAT_CAPACITY: usize = 100;
+
+/// `[mock]` section of `env.toml`
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct MockConfig {
+    pub enabled: bool,
+    /// Port of the EventSub WebSocket server, 0 picks a free one. Not 8080 by default,
+    /// which is the one of `twitch-cli event websocket start-server`
+    pub eventsub_port: u16,
+    /// Port of the fake Helix API, 0 picks a free one
+    pub helix_port: u16,
+    pub keepalive_seconds: u64,
+    /// Synthetic players guessing random letters, ignored when there is a script
+    pub players: usize,
+    /// Time between chat messages
+    pub interval_ms: u64,
+    /// Chat to replay, one `player: message` per line
+    pub script: Option<PathBuf>,
+}
+
+impl Default for MockConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            eventsub_port: 8180,
+            helix_port: 8181,
+            keepalive_seconds: 10,
+            players: 3,
+            interval_ms: 3000,
+            script: None,
+        }
+    }
+}
+
+/// Open sessions of the EventSub server and the id of their chat subscription
+#[derive(Clone, Debug, Default)]
+pub struct Sessions(Arc<Mutex<HashMap<String, Option<String>>>>);
+
+impl Sessions {
+    fn open(&self, session_id: &str) {
+        let mut sessions = self.0.lock().expect("Mock sessions poisoned");
+        sessions.insert(session_id.to_owned(), None);
+    }
+
+    fn close(&self, session_id: &str) {
+        let mut sessions = self.0.lock().expect("Mock sessions poisoned");
+        sessions.remove(session_id);
+    }
+
+    /// Id of the new subscription, `None` when the session does not exist
+    fn subscribe(&self, session_id: &str) -> Option<String> {
+        let mut sessions = self.0.lock().expect("Mock sessions poisoned");
+        let subscription = sessions.get_mut(session_id)?;
+        let id = subscription.get_or_insert_with(random_id);
+        Some(id.clone())
+    }
+
+    fn subscription(&self, session_id: &str) -> Option<String> {
+        let sessions = self.0.lock().expect("Mock sessions poisoned");
+        sessions.get(session_id).cloned().flatten()
+    }
+}
+
+/// Mock servers bound to their ports, nothing runs until `spawn`
+pub struct MockTwitch {
+    config: MockConfig,
+    eventsub_listener: TcpListener,
+    helix_listener: TcpListener,
+    eventsub_url: String,
+    helix_url: String,
+    sessions: Sessions,
+    chat: broadcast::Sender<ChatLine>,
+}
+
+impl MockTwitch {
+    pub async fn bind(config: MockConfig) -> Result<Self, ColgadoLogicError> {
+        let bind = async |port| {
+            let listener = TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], port))).await?;
+            let address = listener.local_addr()?;
+            Ok((listener, address))
+        };
+        let (eventsub_listener, eventsub_address) = bind(config.eventsub_port)
+            .await
+            .map_err(|err| ColgadoLogicError::MockServer { err })?;
+        let (helix_listener, helix_address) = bind(config.helix_port)
+            .await
+            .map_err(|err| ColgadoLogicError::MockServer { err })?;
+        let (chat, _) = broadcast::channel(CHAT_CAPACITY);
+        Ok(Self {
+            config,
+            eventsub_listener,
+            helix_listener,
+            eventsub_url: format!("ws://{eventsub_address}/ws"),
+            helix_url: format!("http://{helix_address}/helix"),
+            sessions: Sessions::default(),
+            chat,
+        })
+    }
+
+    pub fn eventsub_url(&self) -> &str {
+        &self.eventsub_url
+    }
+
+    /// Transport writing in the mock chat as the broadcaster
+    pub fn transport(&self) -> HelixClient {
+        HelixClient::new(self.helix_url.clone(), MOCK_BROADCASTER_ID.to_owned())
+    }
+
+    /// Sender to write in the chat by hand
+    pub fn chat(&self) -> broadcast::Sender<ChatLine> {
+        self.chat.clone()
+    }
+
+    /// Starts the servers and the chat simulator, which only talks while a round is being played
+    pub async fn spawn(
+        self,
+        game_state: watch::Receiver<Option<GameView>>,
+    ) -> Result<Vec<JoinHandle<()>>, ColgadoLogicError> {
+        let source = match &self.config.script {
+            Some(path) => {
+                let text = tokio::fs::read_to_string(path)
+                    .await
+                    .map_err(|err| ColgadoLogicError::MockServer { err })?;
+                ChatSource::script(&text)
+            }
+            None => ChatSource::players(self.config.players),
+        };
+        let keepalive = Duration::from_secs(self.config.keepalive_seconds);
+        let interval = Duration::from_millis(self.config.interval_ms);
+        let eventsub = eventsub::serve(
+            self.eventsub_listener,
+            self.sessions.clone(),
+            self.chat.clone(),
+            keepalive,
+        );
+        let helix = helix::serve(self.helix_listener, self.sessions, self.chat.clone());
+        let simulator = simulator::simulate(source, self.chat, game_state, interval);
+        Ok(vec![
+            tokio::spawn(eventsub),
+            tokio::spawn(helix),
+            tokio::spawn(simulator),
+        ])
+    }
+}
+
+fn random_id() -> String {
+    format!("{:032x}", rand::random::<u128>())
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt;
+    use tokio_tungstenite::connect_async;
+
+    use super::*;
+    use crate::{
+        actors::{
+            game_actor::{GameSetup, TwitchGameHandle},
+            message_actor::TwitchMessageHandle,
+            transport::ChatTransport,
+        },
+        models::{handles::Handles, messages::TwitchMessage},
+    };
+
+    #[tokio::test]
+    async fn chat_through_mock() {
+        let config = MockConfig {
+            eventsub_port: 0,
+            helix_port: 0,
+            ..MockConfig::default()
+        };
+        let mock = MockTwitch::bind(config).await.unwrap();
+        let url = mock.eventsub_url().to_owned();
+        let transport = mock.transport();
+        let chat = mock.chat();
+        let (_, game_state) = watch::channel(None);
+        let tasks = mock.spawn(game_state).await.unwrap();
+
+        let (ws_stream, _) = connect_async(url).await.unwrap();
+        let (_, mut receiver) = ws_stream.split();
+        let mut next = async || {
+            let message = receiver.next().await.unwrap().unwrap();
+            TwitchMessage::try_from(message).unwrap()
+        };
+        let TwitchMessage::WelcomeMessage { session_id, .. } = next().await else {
+            panic!("Welcome expected");
+        };
+        transport.subscribe(&session_id).await.unwrap();
+
+        chat.send(ChatLine::new("ana", "e")).unwrap();
+        let TwitchMessage::PlayerMessage {
+            message_text,
+            player_name,
+            ..
+        } = next().await
+        else {
+            panic!("Chat message expected");
+        };
+        assert_eq!((message_text.as_str(), player_name.as_str()), ("e", "ana"));
+
+        // the messages of the bot arrive to the chat too
+        transport.send("Hola").await.unwrap();
+        let TwitchMessage::PlayerMessage { message_text, .. } = next().await else {
+            panic!("Bot message expected");
+        };
+        assert_eq!(message_text, "Hola");
+        tasks.iter().for_each(JoinHandle::abort);
+    }
+
+    #[tokio::test]
+    async fn shutdown_closes_the_session() {
+        let config = MockConfig {
+            eventsub_port: 0,
+            helix_port: 0,
+            ..MockConfig::default()
+        };
+        let mock = MockTwitch::bind(config).await.unwrap();
+        let url = mock.eventsub_url().to_owned();
+        let sessions = mock.sessions.clone();
+        let (game_handle, game_task) =
+            TwitchGameHandle::new_and_joinhandle(mock.transport(), GameSetup::default());
+        let tasks = mock.spawn(game_handle.watch_game_state()).await.unwrap();
+
+        let (ws_stream, _) = connect_async(&url).await.unwrap();
+        let (message_handle, message_task) =
+            TwitchMessageHandle::new_and_joinhandle(ws_stream, url, game_handle.clone());
+        // the session id is kept once the subscription is done
+        while game_handle.get_id().await.unwrap().is_none() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        let handles = Handles {
+            message_handle,
+            game_handle,
+        };
+        handles.shutdown().await.unwrap();
+        game_task.await.unwrap();
+        message_task.await.unwrap();
+        assert!(sessions.0.lock().unwrap().is_empty());
+        tasks.iter().for_each(JoinHandle::abort);
+    }
+}
@@ -0,0 +1,112 @@
+//! Chat simulator, replays a script or makes up players guessing letters
+use std::{collections::VecDeque, time::Duration};
+
+use rand::seq::{IndexedMutRandom, SliceRandom};
+use tokio::sync::{broadcast, watch};
+
+use crate::models::game_view::{GameOutcome, GameView};
+
+const ALPHABET: &str = "abcdefghijklmnñopqrstuvwxyz";
+
+/// Message written in the mock chat
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChatLine {
+    pub player_id: String,
+    pub player_name: String,
+    pub text: String,
+}
+
+impl ChatLine {
+    /// Message of a player whose id is made from the name
+    pub fn new(player_name: &str, text: &str) -> Self {
+        Self {
+            player_id: format!("mock-{}", player_name.to_lowercase()),
+            player_name: player_name.to_owned(),
+            text: text.to_owned(),
+        }
+    }
+}
+
+pub enum ChatSource {
+    Script(VecDeque<ChatLine>),
+    /// Every player tries the alphabet in their own random order
+    Players(Vec<(String, Vec<char>)>),
+}
+
+impl ChatSource {
+    /// One `player: message` per line, empty lines and lines starting with `#` are skipped
+    pub fn script(text: &str) -> Self {
+        let lines = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let (player_name, message) = line.split_once(':')?;
+                Some(ChatLine::new(player_name.trim(), message.trim()))
+            })
+            .collect();
+        Self::Script(lines)
+    }
+
+    pub fn players(players: usize) -> Self {
+        let players = (1..=players)
+            .map(|i| (format!("jugador{i}"), Vec::new()))
+            .collect();
+        Self::Players(players)
+    }
+
+    /// Next message, `None` once the script is over
+    fn next_line(&mut self) -> Option<ChatLine> {
+        match self {
+            ChatSource::Script(lines) => lines.pop_front(),
+            ChatSource::Players(players) => {
+                let mut rng = rand::rng();
+                let (player_name, letters) = players.choose_mut(&mut rng)?;
+                if letters.is_empty() {
+                    letters.extend(ALPHABET.chars());
+                    letters.shuffle(&mut rng);
+                }
+                let letter = letters.pop()?;
+                Some(ChatLine::new(player_name, &letter.to_string()))
+            }
+        }
+    }
+}
+
+/// Writes in the chat every `interval` while there is a round being played
+pub async fn simulate(
+    mut source: ChatSource,
+    chat: broadcast::Sender<ChatLine>,
+    mut game_state: watch::Receiver<Option<GameView>>,
+    interval: Duration,
+) {
+    loop {
+        tokio::time::sleep(interval).await;
+        let playing = |game: &Option<GameView>| matches!(game, Some(game) if game.outcome == GameOutcome::InProgress);
+        if game_state.wait_for(playing).await.is_err() {
+            break;
+        }
+        let Some(line) = source.next_line() else {
+            crate::log!("[mock chat]: Script finished");
+            break;
+        };
+        let _ = chat.send(line);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn script_lines() {
+        let text = "# ronda 1\nana: a\n\nluis: !colgado casa\nsin formato\n";
+        let mut source = ChatSource::script(text);
+        assert_eq!(source.next_line(), Some(ChatLine::new("ana", "a")));
+        assert_eq!(
+            source.next_line(),
+            Some(ChatLine::new("luis", "!colgado casa"))
+        );
+        assert_eq!(source.next_line(), None);
+    }
+}
@@ -0,0 +1,32 @@
+pub use crate::word::GuessResult;
+
+use std::sync::Arc;
+
+use crate::errors::ColgadoLogicError;
+
+use super::game_view::GameOutcome;
+use super::leaderboard::Leaderboard;
+
+/// Events published by the game actor, the state of the game is published apart
+#[derive(Debug, Clone)]
+pub enum GameEvent {
+    Guess {
+        player_name: String,
+        result: GuessResult,
+    },
+    RoundFinished {
+        outcome: GameOutcome,
+    },
+    LeaderboardChanged(Leaderboard),
+    ConnectionChanged(ConnectionStatus),
+    /// Error that did not stop the actors, the fatal ones need a new connection
+    Error(Arc<ColgadoLogicError>),
+}
+
+/// State of the connection with the Twitch EventSub WebSocket
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionStatus {
+    Connected,
+    Reconnecting,
+    Disconnected,
+}
@@ -0,0 +1,130 @@
+//! EventSub WebSocket messages, only the fields used by the game are kept
+use serde::Deserialize;
+use serde_json::Value;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Envelope {
+    pub metadata: Metadata,
+    pub payload: Payload,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Metadata {
+    pub message_type: String,
+    pub subscription_type: Option<String>,
+}
+
+/// Every field is optional since its content depends on `Metadata::message_type`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Payload {
+    pub session: Option<Session>,
+    /// Depends on `Metadata::subscription_type`
+    pub event: Option<Value>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Session {
+    pub id: String,
+    pub keepalive_timeout_seconds: Option<u64>,
+    pub reconnect_url: Option<String>,
+}
+
+/// Event of the `channel.chat.message` subscription
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChatMessageEvent {
+    pub chatter_user_id: String,
+    pub chatter_user_name: String,
+    pub message_id: String,
+    pub message: ChatMessage,
+    #[serde(default)]
+    pub badges: Vec<Badge>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChatMessage {
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Badge {
+    pub set_id: String,
+}
+
+/// Permissions of a chatter, ordered from the lowest to the highest
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ChatRole {
+    #[default]
+    Viewer,
+    Vip,
+    Moderator,
+    Broadcaster,
+}
+
+impl ChatRole {
+    /// Highest role given by the badges
+    pub fn from_badges(badges: &[Badge]) -> Self {
+        badges
+            .iter()
+            .map(|badge| match badge.set_id.as_str() {
+                "broadcaster" => Self::Broadcaster,
+                "moderator" | "lead_moderator" => Self::Moderator,
+                "vip" => Self::Vip,
+                _ => Self::Viewer,
+            })
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn welcome_message() {
+        let text = r#"{
+            "metadata": {
+                "message_id": "96a3f3b5-5dec-4eed-908e-e11ee657416c",
+                "message_type": "session_welcome",
+                "message_timestamp": "2023-07-19T14:56:51.634234626Z"
+            },
+            "payload": {
+                "session": {
+                    "id": "AQoQILE98gtqShGmLD7AM6yJThAB",
+                    "status": "connected",
+                    "connected_at": "2023-07-19T14:56:51.616329898Z",
+                    "keepalive_timeout_seconds": 10,
+                    "reconnect_url": null
+                }
+            }
+        }"#;
+        let envelope: Envelope = serde_json::from_str(text).unwrap();
+        assert_eq!(envelope.metadata.message_type, "session_welcome");
+        let session = envelope.payload.session.unwrap();
+        assert_eq!(session.id, "AQoQILE98gtqShGmLD7AM6yJThAB");
+        assert_eq!(session.keepalive_timeout_seconds, Some(10));
+    }
+
+    #[test]
+    fn chat_message_event() {
+        let text = r##"{
+            "broadcaster_user_id": "1971641",
+            "broadcaster_user_login": "streamer",
+            "broadcaster_user_name": "streamer",
+            "chatter_user_id": "4145994",
+            "chatter_user_login": "viewer32",
+            "chatter_user_name": "viewer32",
+            "message_id": "cc106a89-1814-919d-454c-f4f2f970aae7",
+            "message": { "text": "!colgado a", "fragments": [] },
+            "color": "#00FF7F",
+            "badges": [{ "set_id": "moderator", "id": "1", "info": "" }],
+            "message_type": "text"
+        }"##;
+        let event: ChatMessageEvent = serde_json::from_str(text).unwrap();
+        assert_eq!(event.chatter_user_name, "viewer32");
+        assert_eq!(event.message.text, "!colgado a");
+        assert_eq!(event.badges[0].set_id, "moderator");
+        assert_eq!(ChatRole::from_badges(&event.badges), ChatRole::Moderator);
+    }
+}
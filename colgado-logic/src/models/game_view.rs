@@ -1,10 +1,26 @@
+use serde::Serialize;
+
 use crate::word::Game;
+pub use crate::word::GameOutcome;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct GameView {
     pub word: String,
+    /// Actual word split at the spaces, used to wrap long phrases
+    pub words: Vec<String>,
     pub letters: String,
     pub is_completed: bool,
+    pub outcome: GameOutcome,
+    pub lives: usize,
+    pub max_lives: usize,
+    /// Full word, only available once the game has finished
+    pub solution: Option<String>,
+    /// Seconds left when the round has a time limit
+    pub remaining_seconds: Option<u64>,
+    /// The round was lost because the time ran out
+    pub timed_out: bool,
+    /// The round was ended from the chat, the end is already announced
+    pub ended: bool,
 }
 
 impl GameView {
@@ -13,16 +29,28 @@ impl GameView {
             word,
             letters,
             is_completed,
+            ..Default::default()
         }
     }
 }
 
 impl From<&Game> for GameView {
     fn from(value: &Game) -> Self {
+        let outcome = value.outcome();
+        let is_completed = outcome != GameOutcome::InProgress;
+        let solution = is_completed.then(|| value.get_word());
         Self {
             word: value.get_actual_word(),
+            words: value.get_actual_words(),
             letters: value.get_letters(),
-            is_completed: value.is_completed(),
+            is_completed,
+            outcome,
+            lives: value.remaining_lives(),
+            max_lives: value.max_lives(),
+            solution,
+            remaining_seconds: None,
+            timed_out: value.is_timed_out(),
+            ended: value.is_ended(),
         }
     }
 }
@@ -31,8 +59,16 @@ impl Default for GameView {
     fn default() -> Self {
         Self {
             word: String::default(),
+            words: Vec::new(),
             letters: String::default(),
             is_completed: true,
+            outcome: GameOutcome::default(),
+            lives: 0,
+            max_lives: 0,
+            solution: None,
+            remaining_seconds: None,
+            timed_out: false,
+            ended: false,
         }
     }
 }
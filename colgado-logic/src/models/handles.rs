@@ -1,7 +1,27 @@
+use std::time::Duration;
+
 use crate::actors::{game_actor::TwitchGameHandle, message_actor::TwitchMessageHandle};
+use crate::errors::ColgadoLogicError;
+
+/// Time given to the actors to stop before the apps abort their tasks
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
 
 #[derive(Debug, Clone)]
 pub struct Handles {
     pub message_handle: TwitchMessageHandle,
     pub game_handle: TwitchGameHandle,
 }
+
+impl Handles {
+    /// Stops the game actor and then closes the connection, errors with `ShutdownTimeout`
+    /// when it takes too long, the tasks left have to be aborted after that
+    pub async fn shutdown(&self) -> Result<(), ColgadoLogicError> {
+        let shutdown = async {
+            self.game_handle.shutdown().await?;
+            self.message_handle.shutdown().await
+        };
+        tokio::time::timeout(SHUTDOWN_TIMEOUT, shutdown)
+            .await
+            .map_err(|_| ColgadoLogicError::ShutdownTimeout)?
+    }
+}
@@ -0,0 +1,3 @@
+pub use crate::score::PlayerScore;
+
+pub type Leaderboard = Vec<PlayerScore>;
@@ -1,13 +1,22 @@
-use serde_json::Value;
 use tokio::sync::oneshot;
 use tokio_tungstenite::tungstenite::Message;
 
+use crate::errors::{ColgadoLogicError, MessageError};
+use crate::settings::Settings;
+use crate::word_bank::RandomWord;
+
+use super::events::ConnectionStatus;
+use super::eventsub::{ChatMessageEvent, ChatRole, Envelope};
 use super::game_view::GameView;
+use super::leaderboard::Leaderboard;
 #[derive(Debug)]
 pub enum GeneralMessage {
     CommandMessage(CommandMessage),
     TwitchMessage(TwitchMessage),
     TwitchSendMessage(String),
+    ConnectionStatus(ConnectionStatus),
+    /// Failure of the message actor, published as a game event
+    Error(ColgadoLogicError),
 }
 
 /// Messages received from the app
@@ -19,10 +28,30 @@ pub enum CommandMessage {
     GetGameState {
         sender: oneshot::Sender<Option<GameView>>,
     },
+    /// Starts a round with the word, the word sent back is the hidden one
     SetGameWord {
         word: String,
-        sender: oneshot::Sender<String>,
+        sender: oneshot::Sender<Result<String, ColgadoLogicError>>,
+    },
+    GetLeaderboard {
+        sender: oneshot::Sender<Leaderboard>,
+    },
+    /// Starts a round with a word of the bank, the word sent back is the hidden one
+    SetRandomWord {
+        category: Option<String>,
+        sender: oneshot::Sender<Option<RandomWord>>,
+    },
+    GetCategories {
+        sender: oneshot::Sender<Vec<String>>,
     },
+    /// Uses the settings from now on, the round in progress keeps its rules
+    ApplySettings {
+        settings: Box<Settings>,
+        sender: oneshot::Sender<()>,
+    },
+    /// Stops the actor, answered once its work is done: the game actor sends the queued
+    /// chat messages, unsubscribes and saves the stats, the message actor closes the WebSocket
+    Shutdown { sender: oneshot::Sender<()> },
 }
 /// Mesages received from Twitch
 #[derive(Debug)]
@@ -35,9 +64,18 @@ pub enum TwitchMessage {
         message_id: String,
         player_id: String,
         player_name: String,
+        role: ChatRole,
     },
     WelcomeMessage {
         session_id: String,
+        keepalive_timeout_seconds: Option<u64>,
+    },
+    ReconnectMessage {
+        reconnect_url: String,
+    },
+    /// Welcome received after a reconnect message, the subscriptions are kept
+    SessionReconnected {
+        session_id: String,
     },
     OtherText {
         text: String,
@@ -48,53 +86,118 @@ pub enum TwitchMessage {
     None,
 }
 
-impl TwitchMessage {
-    fn from_message_text(value: &str) -> Self {
-        let v: Value = serde_json::from_str(value).expect("Error deserializing message");
-        let message_type = &v["metadata"]["message_type"];
-        let message_type = message_type.as_str();
-        match message_type {
-            Some("notification") => {
-                let text = &v["payload"]["event"]["message"]["text"];
-                let text = text.as_str().unwrap_or_default().to_owned();
-
-                let message_id = &v["payload"]["event"]["message_id"];
-                let message_id = message_id.as_str().unwrap_or_default().to_owned();
+const CHAT_MESSAGE_SUBSCRIPTION: &str = "channel.chat.message";
 
-                let player_id = &v["payload"]["event"]["chatter_user_id"];
-                let player_id = player_id.as_str().unwrap_or_default().to_owned();
-
-                let player = &v["payload"]["event"]["chatter_user_name"];
-                let player = player.as_str().unwrap_or_default().to_owned();
-                Self::PlayerMessage {
-                    message_text: text,
-                    message_id,
-                    player_id,
-                    player_name: player,
+impl TwitchMessage {
+    fn from_message_text(value: &str) -> Result<Self, MessageError> {
+        let Envelope { metadata, payload } = serde_json::from_str(value)?;
+        let missing_field = |field| MessageError::MissingField {
+            message_type: metadata.message_type.clone(),
+            field,
+        };
+        match metadata.message_type.as_str() {
+            "notification" => {
+                let subscription_type = metadata
+                    .subscription_type
+                    .clone()
+                    .ok_or_else(|| missing_field("subscription_type"))?;
+                if subscription_type != CHAT_MESSAGE_SUBSCRIPTION {
+                    return Err(MessageError::UnknownSubscription { subscription_type });
                 }
+                let event = payload.event.ok_or_else(|| missing_field("event"))?;
+                let event: ChatMessageEvent = serde_json::from_value(event)?;
+                let role = ChatRole::from_badges(&event.badges);
+                Ok(Self::PlayerMessage {
+                    message_text: event.message.text,
+                    message_id: event.message_id,
+                    player_id: event.chatter_user_id,
+                    player_name: event.chatter_user_name,
+                    role,
+                })
             }
-            Some("session_welcome") => {
-                let session_id = &v["payload"]["session"]["id"];
-                let session_id = session_id.as_str().unwrap_or_default().to_owned();
-                Self::WelcomeMessage { session_id }
+            "session_welcome" => {
+                let session = payload.session.ok_or_else(|| missing_field("session"))?;
+                Ok(Self::WelcomeMessage {
+                    session_id: session.id,
+                    keepalive_timeout_seconds: session.keepalive_timeout_seconds,
+                })
             }
-            Some("session_keepalive") => Self::None,
-            Some(message_type) => {
-                println!("[WARNING] Type not handled {message_type}");
+            "session_keepalive" => Ok(Self::None),
+            "session_reconnect" => {
+                let reconnect_url = payload
+                    .session
+                    .and_then(|session| session.reconnect_url)
+                    .ok_or_else(|| missing_field("reconnect_url"))?;
+                Ok(Self::ReconnectMessage { reconnect_url })
+            }
+            "revocation" => {
+                crate::log!("[WARNING] Type not handled {}", metadata.message_type);
                 let text = value.to_owned();
-                Self::OtherText { text }
+                Ok(Self::OtherText { text })
             }
-            None => Self::None,
+            _ => Err(MessageError::UnknownType {
+                message_type: metadata.message_type,
+            }),
         }
     }
 }
 
-impl From<Message> for TwitchMessage {
-    fn from(value: Message) -> Self {
-        let message: Self = match value {
+impl TryFrom<Message> for TwitchMessage {
+    type Error = MessageError;
+
+    fn try_from(value: Message) -> Result<Self, Self::Error> {
+        match value {
             Message::Text(msg_text) => Self::from_message_text(&msg_text),
-            _ => Self::Other { mesage: value },
-        };
-        message
+            _ => Ok(Self::Other { mesage: value }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Result<TwitchMessage, MessageError> {
+        TwitchMessage::try_from(Message::text(text))
+    }
+
+    fn envelope(message_type: &str, payload: &str) -> String {
+        format!(
+            r#"{{
+                "metadata": {{
+                    "message_id": "1",
+                    "message_type": "{message_type}",
+                    "message_timestamp": "2023-07-19T14:56:51.634234626Z"
+                }},
+                "payload": {payload}
+            }}"#
+        )
+    }
+
+    #[test]
+    fn invalid_frames() {
+        assert!(matches!(
+            parse("{not json"),
+            Err(MessageError::Malformed { .. })
+        ));
+        assert!(matches!(
+            parse(r#"{ "payload": {} }"#),
+            Err(MessageError::Malformed { .. })
+        ));
+        assert!(matches!(
+            parse(&envelope("session_goodbye", "{}")),
+            Err(MessageError::UnknownType { message_type }) if message_type == "session_goodbye"
+        ));
+        assert!(matches!(
+            parse(&envelope("session_welcome", "{}")),
+            Err(MessageError::MissingField {
+                field: "session",
+                ..
+            })
+        ));
+        assert!(matches!(
+            parse(&envelope("session_keepalive", "{}")),
+            Ok(TwitchMessage::None)
+        ));
     }
 }
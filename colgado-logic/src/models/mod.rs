@@ -1,3 +1,6 @@
+pub mod events;
+pub mod eventsub;
 pub mod game_view;
 pub mod handles;
+pub mod leaderboard;
 pub mod messages;
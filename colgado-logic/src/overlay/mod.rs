@@ -0,0 +1,301 @@
+//! Local HTTP server with a page to add as an OBS browser source, the page is updated
+//! through server-sent events every time the game changes
+use std::{convert::Infallible, net::SocketAddr, sync::Arc, time::Duration};
+
+use bytes::Bytes;
+use futures_util::{stream, StreamExt};
+use http_body_util::{combinators::UnsyncBoxBody, BodyExt, Full, StreamBody};
+use hyper::{
+    body::{Frame, Incoming},
+    header,
+    server::conn::http1,
+    service::service_fn,
+    Method, Request, Response, StatusCode,
+};
+use hyper_util::rt::TokioIo;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    net::TcpListener,
+    sync::{
+        broadcast::{self, error::RecvError},
+        watch,
+    },
+    task::JoinHandle,
+};
+
+use crate::{
+    actors::game_actor::TwitchGameHandle,
+    errors::ColgadoLogicError,
+    i18n::Catalog,
+    models::{events::GameEvent, game_view::GameView, leaderboard::Leaderboard},
+};
+
+const PAGE: &str = include_str!("index.html");
+/// Comment sent to the idle connections so they are not closed
+const SSE_KEEPALIVE: Duration = Duration::from_secs(15);
+
+type Body = UnsyncBoxBody<Bytes, Infallible>;
+
+/// `[overlay]` section of `env.toml`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct OverlayConfig {
+    pub enabled: bool,
+    /// Port of `http://localhost:<port>`, 0 picks a free one
+    pub port: u16,
+    /// Players shown in the leaderboard of the page
+    pub leaderboard_size: usize,
+}
+
+impl Default for OverlayConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            port: 8090,
+            leaderboard_size: 5,
+        }
+    }
+}
+
+/// What the page shows
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct OverlayState {
+    pub game: Option<GameView>,
+    pub leaderboard: Leaderboard,
+}
+
+/// Overlay server bound to its port, nothing runs until `spawn`
+pub struct OverlayServer {
+    config: OverlayConfig,
+    listener: TcpListener,
+    address: SocketAddr,
+    page: Arc<str>,
+}
+
+impl OverlayServer {
+    pub async fn bind(config: OverlayConfig) -> Result<Self, ColgadoLogicError> {
+        let bind = async || {
+            let address = SocketAddr::from(([127, 0, 0, 1], config.port));
+            let listener = TcpListener::bind(address).await?;
+            let address = listener.local_addr()?;
+            Ok((listener, address))
+        };
+        let (listener, address) = bind()
+            .await
+            .map_err(|err| ColgadoLogicError::OverlayServer { err })?;
+        Ok(Self {
+            config,
+            listener,
+            address,
+            page: render_page(Catalog::default()).into(),
+        })
+    }
+
+    /// Sets the language of the texts of the page
+    pub fn with_catalog(mut self, catalog: Catalog) -> Self {
+        self.page = render_page(catalog).into();
+        self
+    }
+
+    /// Address to add in OBS
+    pub fn url(&self) -> String {
+        format!("http://localhost:{}/", self.address.port())
+    }
+
+    /// Starts following the game and serving the page
+    pub async fn spawn(self, game_handle: &TwitchGameHandle) -> Vec<JoinHandle<()>> {
+        let mut game_states = game_handle.watch_game_state();
+        let events = game_handle.subscribe_events();
+        let game = game_states.borrow_and_update().clone();
+        let leaderboard = game_handle.get_leaderboard().await.unwrap_or_default();
+        let state = OverlayState { game, leaderboard };
+        let (state_sender, state_receiver) = watch::channel(String::new());
+        let mut follower = GameFollower {
+            state,
+            state_sender,
+            leaderboard_size: self.config.leaderboard_size,
+        };
+        follower.publish();
+        let follow = follower.run(game_states, events);
+        let serve = serve(self.listener, self.page, state_receiver);
+        vec![tokio::spawn(follow), tokio::spawn(serve)]
+    }
+}
+
+/// Keeps the JSON of the page up to date
+struct GameFollower {
+    state: OverlayState,
+    state_sender: watch::Sender<String>,
+    leaderboard_size: usize,
+}
+
+impl GameFollower {
+    fn publish(&mut self) {
+        self.state.leaderboard.truncate(self.leaderboard_size);
+        match serde_json::to_string(&self.state) {
+            Ok(json) => {
+                self.state_sender.send_replace(json);
+            }
+            Err(err) => crate::log_error!("[overlay]: {err}"),
+        }
+    }
+
+    /// Ends with the game actor
+    async fn run(
+        mut self,
+        mut game_states: watch::Receiver<Option<GameView>>,
+        mut events: broadcast::Receiver<GameEvent>,
+    ) {
+        loop {
+            tokio::select! {
+                changed = game_states.changed() => match changed {
+                    Ok(()) => self.state.game = game_states.borrow_and_update().clone(),
+                    Err(_) => break,
+                },
+                event = events.recv() => match event {
+                    Ok(GameEvent::LeaderboardChanged(leaderboard)) => {
+                        self.state.leaderboard = leaderboard;
+                    }
+                    Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                },
+            }
+            self.publish();
+        }
+    }
+}
+
+/// Page with the texts of the catalog
+fn render_page(catalog: Catalog) -> String {
+    let texts = serde_json::json!({
+        "won": catalog.text("overlay.won"),
+        "lost": catalog.text("overlay.lost"),
+        "timed_out": catalog.text("overlay.timed-out"),
+    });
+    PAGE.replace("{lang}", catalog.language().code())
+        .replace("{texts}", &texts.to_string())
+}
+
+async fn serve(listener: TcpListener, page: Arc<str>, state: watch::Receiver<String>) {
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(err) => {
+                crate::log_error!("[overlay]: {err}");
+                continue;
+            }
+        };
+        let page = page.clone();
+        let state = state.clone();
+        let service = service_fn(move |request| {
+            let page = page.clone();
+            let state = state.clone();
+            async move { Ok::<_, Infallible>(handle(request, &page, state)) }
+        });
+        tokio::spawn(async move {
+            let connection = http1::Builder::new().serve_connection(TokioIo::new(stream), service);
+            if let Err(err) = connection.await {
+                crate::log_error!("[overlay]: {err}");
+            }
+        });
+    }
+}
+
+fn handle(
+    request: Request<Incoming>,
+    page: &str,
+    state: watch::Receiver<String>,
+) -> Response<Body> {
+    match (request.method(), request.uri().path()) {
+        (&Method::GET, "/") => response("text/html; charset=utf-8", page.to_owned()),
+        (&Method::GET, "/state") => {
+            let json = state.borrow().clone();
+            response("application/json", json)
+        }
+        (&Method::GET, "/events") => events(state),
+        _ => {
+            let mut response = response("text/plain", "Not Found".to_owned());
+            *response.status_mut() = StatusCode::NOT_FOUND;
+            response
+        }
+    }
+}
+
+fn response(content_type: &'static str, body: String) -> Response<Body> {
+    let body = Full::new(Bytes::from(body)).boxed_unsync();
+    let mut response = Response::new(body);
+    let content_type = header::HeaderValue::from_static(content_type);
+    response
+        .headers_mut()
+        .insert(header::CONTENT_TYPE, content_type);
+    response
+}
+
+/// Server-sent events with the state, the current one is sent first
+fn events(mut state: watch::Receiver<String>) -> Response<Body> {
+    state.mark_changed();
+    let events = stream::unfold(state, |mut state| async move {
+        let event = match tokio::time::timeout(SSE_KEEPALIVE, state.changed()).await {
+            Ok(Ok(())) => format!("data: {}\n\n", *state.borrow_and_update()),
+            Ok(Err(_)) => return None,
+            Err(_) => ":\n\n".to_owned(),
+        };
+        Some((Ok(Frame::data(Bytes::from(event))), state))
+    });
+    let body = StreamBody::new(events.boxed()).boxed_unsync();
+    let mut response = Response::new(body);
+    let headers = response.headers_mut();
+    headers.insert(
+        header::CONTENT_TYPE,
+        header::HeaderValue::from_static("text/event-stream"),
+    );
+    headers.insert(
+        header::CACHE_CONTROL,
+        header::HeaderValue::from_static("no-cache"),
+    );
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use http_body_util::Empty;
+    use hyper_util::{client::legacy::Client, rt::TokioExecutor};
+
+    use super::*;
+    use crate::{
+        actors::{game_actor::GameSetup, transport::MemoryTransport},
+        i18n::Language,
+    };
+
+    #[tokio::test]
+    async fn serves_the_state() {
+        let (game_handle, _) =
+            TwitchGameHandle::new_and_joinhandle(MemoryTransport::default(), GameSetup::default());
+        game_handle.set_game_word("casa".to_owned()).await.unwrap();
+        let config = OverlayConfig {
+            port: 0,
+            ..OverlayConfig::default()
+        };
+        let server = OverlayServer::bind(config)
+            .await
+            .unwrap()
+            .with_catalog(Catalog::new(Language::En));
+        let url = server.url();
+        let tasks = server.spawn(&game_handle).await;
+
+        let client = Client::builder(TokioExecutor::new()).build_http::<Empty<Bytes>>();
+        let response = client.get(url.parse().unwrap()).await.unwrap();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let page = String::from_utf8(body.to_vec()).unwrap();
+        assert!(page.contains(r#"<html lang="en">"#));
+        assert!(page.contains(r#""won":"Word guessed!""#));
+
+        let url = format!("{url}state");
+        let response = client.get(url.parse().unwrap()).await.unwrap();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let state: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(state["game"]["word"], "____");
+        assert_eq!(state["game"]["outcome"], "in-progress");
+        tasks.iter().for_each(JoinHandle::abort);
+    }
+}
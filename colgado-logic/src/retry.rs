@@ -0,0 +1,80 @@
+use std::{fmt::Display, future::Future, time::Duration};
+
+/// How many times a failed request is tried and how long to wait between tries
+#[derive(Clone, Copy, Debug)]
+pub struct RetryPolicy {
+    pub attempts: u32,
+    /// Delay after the first failure, it doubles after each one
+    pub delay: Duration,
+}
+
+impl RetryPolicy {
+    /// Delay after the given number of failures
+    pub fn delay(&self, failures: u32) -> Duration {
+        self.delay
+            .saturating_mul(2u32.saturating_pow(failures.saturating_sub(1)))
+    }
+
+    pub async fn run<T, E, F, Fut>(&self, name: &str, mut operation: F) -> Result<T, E>
+    where
+        E: Display,
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let mut attempt = 1;
+        loop {
+            match operation().await {
+                Ok(value) => return Ok(value),
+                Err(err) if attempt < self.attempts => {
+                    crate::log_error!(
+                        "[retry]: {name} failed ({attempt}/{}): {err}",
+                        self.attempts
+                    );
+                    tokio::time::sleep(self.delay(attempt)).await;
+                    attempt += 1;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            attempts: 3,
+            delay: Duration::from_millis(500),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn retry_until_success() {
+        let policy = RetryPolicy {
+            attempts: 3,
+            delay: Duration::from_millis(1),
+        };
+        let mut tries = 0;
+        let result: Result<u32, String> = policy
+            .run("test", || {
+                tries += 1;
+                let tries = tries;
+                async move {
+                    if tries < 3 {
+                        Err(format!("try {tries}"))
+                    } else {
+                        Ok(tries)
+                    }
+                }
+            })
+            .await;
+        assert_eq!(result, Ok(3));
+
+        let result: Result<(), &str> = policy.run("test", || async { Err("fail") }).await;
+        assert_eq!(result, Err("fail"));
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::word::{GameOutcome, GuessResult};
+
+/// Points given or taken for each kind of guess
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ScoreWeights {
+    /// Points for each grapheme revealed
+    pub letter: i64,
+    /// Points for guessing the whole word
+    pub solve: i64,
+    /// Points for a wrong whole-word guess, usually negative
+    pub wrong_solve: i64,
+}
+
+impl Default for ScoreWeights {
+    fn default() -> Self {
+        Self {
+            letter: 1,
+            solve: 10,
+            wrong_solve: -2,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct PlayerScore {
+    pub player_id: String,
+    pub player_name: String,
+    pub points: i64,
+    pub letters: usize,
+    pub wins: usize,
+}
+
+/// Scores of every player during a session, kept between rounds
+#[derive(Clone, Debug, Default)]
+pub struct Scoreboard {
+    weights: ScoreWeights,
+    players: HashMap<String, PlayerScore>,
+}
+
+impl Scoreboard {
+    pub fn new(weights: ScoreWeights) -> Self {
+        Self {
+            weights,
+            players: HashMap::new(),
+        }
+    }
+
+    /// Points of the guesses recorded from now on, the scores are kept
+    pub fn set_weights(&mut self, weights: ScoreWeights) {
+        self.weights = weights;
+    }
+
+    /// Credits the player with the result of a guess, `outcome` is the state of the game after it.
+    /// Returns the points given, if any
+    pub fn record(
+        &mut self,
+        player_id: &str,
+        player_name: &str,
+        result: GuessResult,
+        outcome: GameOutcome,
+    ) -> Option<i64> {
+        let points = match result {
+            GuessResult::LetterFound(found) => self.weights.letter * found as i64,
+            GuessResult::Solved => self.weights.solve,
+            GuessResult::WrongSolve => self.weights.wrong_solve,
+            GuessResult::LetterMissed | GuessResult::Ignored => return None,
+        };
+        let player = self
+            .players
+            .entry(player_id.to_owned())
+            .or_insert_with(|| PlayerScore {
+                player_id: player_id.to_owned(),
+                ..Default::default()
+            });
+        player.player_name = player_name.to_owned();
+        player.points += points;
+        if let GuessResult::LetterFound(found) = result {
+            player.letters += found;
+        }
+        let winner = matches!(result, GuessResult::LetterFound(_) | GuessResult::Solved);
+        if winner && outcome == GameOutcome::Won {
+            player.wins += 1;
+        }
+        Some(points)
+    }
+
+    /// Players sorted from the highest score to the lowest
+    pub fn leaderboard(&self) -> Vec<PlayerScore> {
+        let mut leaderboard: Vec<PlayerScore> = self.players.values().cloned().collect();
+        leaderboard.sort_by(|a, b| {
+            b.points
+                .cmp(&a.points)
+                .then_with(|| a.player_name.cmp(&b.player_name))
+        });
+        leaderboard
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_guesses() {
+        let mut scoreboard = Scoreboard::default();
+        let in_progress = GameOutcome::InProgress;
+        scoreboard.record("1", "ana", GuessResult::LetterFound(2), in_progress);
+        scoreboard.record("2", "luis", GuessResult::WrongSolve, in_progress);
+        scoreboard.record("2", "luis", GuessResult::LetterMissed, in_progress);
+        scoreboard.record("2", "Luis", GuessResult::Solved, GameOutcome::Won);
+
+        let leaderboard = scoreboard.leaderboard();
+        assert_eq!(leaderboard.len(), 2);
+        assert_eq!(leaderboard[0].player_name, "Luis");
+        assert_eq!(leaderboard[0].points, 8);
+        assert_eq!(leaderboard[0].wins, 1);
+        assert_eq!(leaderboard[1].player_id, "1");
+        assert_eq!(leaderboard[1].points, 2);
+        assert_eq!(leaderboard[1].letters, 2);
+        assert_eq!(leaderboard[1].wins, 0);
+    }
+
+    #[test]
+    fn last_letter_wins() {
+        let mut scoreboard = Scoreboard::default();
+        scoreboard.record("1", "ana", GuessResult::LetterFound(1), GameOutcome::Won);
+        assert_eq!(scoreboard.leaderboard()[0].wins, 1);
+    }
+}
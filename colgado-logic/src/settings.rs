@@ -0,0 +1,239 @@
+//! Options changed from the settings screen of the apps and saved back to `env.toml`
+use std::path::Path;
+
+use serde::Serialize;
+use toml::{Table, Value};
+use toml_edit::{DocumentMut, InlineTable, Item};
+
+use crate::{
+    config::Config,
+    errors::{ColgadoLogicError, InvalidValue},
+    game_config::GameConfig,
+    i18n::Language,
+    overlay::OverlayConfig,
+};
+
+/// Part of `env.toml` that can be edited while the app runs
+///
+/// The command, the rules and the chat language are applied to the running game,
+/// the Twitch app and the overlay are used from the next start
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Settings {
+    /// Prefix of the whole-word guesses and of the subcommands, like `!colgado`
+    pub command: String,
+    pub client_id: String,
+    pub language: Language,
+    pub ui_language: Option<Language>,
+    pub chat_language: Option<Language>,
+    pub game: GameConfig,
+    pub overlay: Option<OverlayConfig>,
+}
+
+impl Settings {
+    pub fn ui_language(&self) -> Language {
+        self.ui_language.unwrap_or(self.language)
+    }
+
+    pub fn chat_language(&self) -> Language {
+        self.chat_language.unwrap_or(self.language)
+    }
+
+    pub fn validate(&self) -> Result<(), InvalidValue> {
+        if self.command.is_empty() || self.command.contains(char::is_whitespace) {
+            return Err(InvalidValue::new("command", "must be a single word"));
+        }
+        self.game.validate()
+    }
+
+    /// Writes the settings that changed to the file, the rest of it is kept as it was,
+    /// comments included. The file is written to a temporary one first and moved over the old one
+    pub async fn save(&self, path: impl AsRef<Path>) -> Result<(), ColgadoLogicError> {
+        let path = path.as_ref();
+        self.validate()
+            .map_err(|err| ColgadoLogicError::InvalidConfig {
+                path: path.to_owned(),
+                err,
+            })?;
+        let text = match tokio::fs::read_to_string(path).await {
+            Ok(text) => text,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(ColgadoLogicError::ConfigIo { err }),
+        };
+        let old: Config = toml::from_str(&text).map_err(|err| ColgadoLogicError::ConfigFormat {
+            path: path.to_owned(),
+            err,
+        })?;
+        let mut document: DocumentMut =
+            text.parse().map_err(|err| ColgadoLogicError::ConfigEdit {
+                path: path.to_owned(),
+                err,
+            })?;
+        let serialize_error = |err| ColgadoLogicError::ConfigSerialize { err };
+        let old = table(&old.settings()).map_err(serialize_error)?;
+        let new = table(self).map_err(serialize_error)?;
+        merge(document.as_item_mut(), &old, new);
+
+        let save_error = |err| ColgadoLogicError::ConfigSave {
+            path: path.to_owned(),
+            err,
+        };
+        let tmp_path = path.with_extension("toml.tmp");
+        tokio::fs::write(&tmp_path, document.to_string())
+            .await
+            .map_err(save_error)?;
+        tokio::fs::rename(&tmp_path, path).await.map_err(save_error)
+    }
+}
+
+/// Keys of `env.toml` for the settings, the missing options have no key
+fn table(settings: &Settings) -> Result<Table, toml::ser::Error> {
+    match Value::try_from(settings)? {
+        Value::Table(table) => Ok(table),
+        _ => Ok(Table::new()),
+    }
+}
+
+/// Writes the keys that differ from the old ones and removes the ones that are gone,
+/// the rest of the file is not touched and the changed values keep their comments
+fn merge(parent: &mut Item, old: &Table, new: Table) {
+    // new sections are written like their parent, as `[section]` or inline
+    let new_table = if parent.is_table() {
+        toml_edit::table()
+    } else {
+        Item::Value(InlineTable::new().into())
+    };
+    let Some(parent) = parent.as_table_like_mut() else {
+        return;
+    };
+    for key in old.keys().filter(|key| !new.contains_key(*key)) {
+        parent.remove(key);
+    }
+    for (key, value) in new {
+        let old_value = old.get(&key);
+        if old_value == Some(&value) {
+            continue;
+        }
+        match value {
+            Value::Table(values) => {
+                if !parent.get(&key).is_some_and(Item::is_table_like) {
+                    parent.insert(&key, new_table.clone());
+                }
+                let Some(item) = parent.get_mut(&key) else {
+                    continue;
+                };
+                let empty = Table::new();
+                let old = old_value.and_then(Value::as_table).unwrap_or(&empty);
+                merge(item, old, values);
+            }
+            value => {
+                let mut value = edit_value(value);
+                match parent.get_mut(&key) {
+                    Some(item) => {
+                        if let Some(old_value) = item.as_value() {
+                            *value.decor_mut() = old_value.decor().clone();
+                        }
+                        *item = Item::Value(value);
+                    }
+                    None => {
+                        parent.insert(&key, Item::Value(value));
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn edit_value(value: Value) -> toml_edit::Value {
+    match value {
+        Value::String(string) => string.into(),
+        Value::Integer(integer) => integer.into(),
+        Value::Float(float) => float.into(),
+        Value::Boolean(boolean) => boolean.into(),
+        Value::Datetime(datetime) => {
+            let text = datetime.to_string();
+            text.parse::<toml_edit::Datetime>()
+                .map_or_else(|_| text.into(), Into::into)
+        }
+        Value::Array(values) => values
+            .into_iter()
+            .map(edit_value)
+            .collect::<toml_edit::Array>()
+            .into(),
+        Value::Table(values) => values
+            .into_iter()
+            .map(|(key, value)| (key, edit_value(value)))
+            .collect::<InlineTable>()
+            .into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::TestDir;
+
+    #[tokio::test]
+    async fn save_keeps_other_keys() {
+        let dir = TestDir::new("settings");
+        let path = dir.path("env.toml");
+        let text = r#"# Twitch app
+client-id = "abc"
+redirect-urls = ["http://localhost:3000"]
+command = "!colgado" # prefix of the guesses
+chat-language = "en"
+
+[game]
+lives = 6 # failed guesses
+max-word-length = 10
+
+[game.timer]
+round-seconds = 90
+"#;
+        tokio::fs::write(&path, text).await.unwrap();
+
+        let mut settings = Config::load(&path).await.unwrap().settings();
+        settings.command = "!horca".to_owned();
+        settings.chat_language = None;
+        settings.game.lives = 8;
+        settings.game.max_word_length = None;
+        settings.game.announce.win = false;
+        if let Some(timer) = &mut settings.game.timer {
+            timer.hint_seconds = 0;
+        }
+        settings.overlay = Some(OverlayConfig::default());
+        settings.save(&path).await.unwrap();
+
+        let config = Config::load(&path).await.unwrap();
+        assert_eq!(config.settings(), settings);
+        let expected = r#"# Twitch app
+client-id = "abc"
+redirect-urls = ["http://localhost:3000"]
+command = "!horca" # prefix of the guesses
+
+[game]
+lives = 8 # failed guesses
+
+[game.timer]
+round-seconds = 90
+hint-seconds = 0
+
+[game.announce]
+win = false
+
+[overlay]
+enabled = true
+leaderboard-size = 5
+port = 8090
+"#;
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), expected);
+
+        settings.command = "!el colgado".to_owned();
+        let result = settings.save(&path).await;
+        assert!(matches!(
+            result,
+            Err(ColgadoLogicError::InvalidConfig { err, .. }) if err.key == "command"
+        ));
+        assert_eq!(Config::load(&path).await.unwrap().command, "!horca");
+    }
+}
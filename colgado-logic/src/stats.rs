@@ -0,0 +1,134 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{errors::ColgadoLogicError, word::GuessResult};
+
+/// Totals of a player across every stream
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct PlayerStats {
+    pub player_name: String,
+    pub points: i64,
+    pub wins: usize,
+    pub letters: usize,
+    /// Rounds won in a row
+    pub streak: usize,
+    pub best_streak: usize,
+}
+
+/// Player statistics saved in a file, keyed by the Twitch user id so renamed users keep their history
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct StatsStore {
+    #[serde(skip)]
+    path: PathBuf,
+    #[serde(default)]
+    players: HashMap<String, PlayerStats>,
+    /// Players who guessed in the current round
+    #[serde(skip)]
+    round_players: HashSet<String>,
+}
+
+impl StatsStore {
+    /// Reads the store, a missing file is an empty store
+    pub async fn load(path: impl AsRef<Path>) -> Result<Self, ColgadoLogicError> {
+        let path = path.as_ref();
+        let mut store = match tokio::fs::read_to_string(path).await {
+            Ok(text) => toml::from_str(&text).map_err(|err| ColgadoLogicError::StatsFormat {
+                path: path.to_owned(),
+                err,
+            })?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Self::default(),
+            Err(err) => return Err(ColgadoLogicError::StatsIo { err }),
+        };
+        store.path = path.to_owned();
+        Ok(store)
+    }
+
+    /// Writes the store to a temporary file and moves it over the old one
+    pub async fn save(&self) -> Result<(), ColgadoLogicError> {
+        let text =
+            toml::to_string(self).map_err(|err| ColgadoLogicError::StatsSerialize { err })?;
+        let tmp_path = self.path.with_extension("toml.tmp");
+        tokio::fs::write(&tmp_path, text).await?;
+        tokio::fs::rename(&tmp_path, &self.path).await?;
+        Ok(())
+    }
+
+    pub fn get(&self, player_id: &str) -> Option<&PlayerStats> {
+        self.players.get(player_id)
+    }
+
+    /// Adds the points and letters of a guess to the player, who takes part in the round
+    pub fn record(&mut self, player_id: &str, player_name: &str, result: GuessResult, points: i64) {
+        self.round_players.insert(player_id.to_owned());
+        let player = self.players.entry(player_id.to_owned()).or_default();
+        player.player_name = player_name.to_owned();
+        player.points += points;
+        if let GuessResult::LetterFound(found) = result {
+            player.letters += found;
+        }
+    }
+
+    /// Counts the win and updates the streaks, the other players of the round lose their streak
+    pub fn end_round(&mut self, winner_id: Option<&str>) {
+        for player_id in self.round_players.drain() {
+            let Some(player) = self.players.get_mut(&player_id) else {
+                continue;
+            };
+            if Some(player_id.as_str()) == winner_id {
+                player.wins += 1;
+                player.streak += 1;
+                player.best_streak = player.best_streak.max(player.streak);
+            } else {
+                player.streak = 0;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::TestDir;
+
+    #[test]
+    fn streaks() {
+        let mut store = StatsStore::default();
+        store.record("1", "ana", GuessResult::LetterFound(2), 2);
+        store.record("2", "luis", GuessResult::Solved, 10);
+        store.end_round(Some("2"));
+        store.record("2", "luis", GuessResult::Solved, 10);
+        store.end_round(Some("2"));
+        store.record("1", "ana", GuessResult::Solved, 10);
+        store.record("2", "luis", GuessResult::LetterMissed, 0);
+        store.end_round(Some("1"));
+
+        let ana = store.get("1").unwrap();
+        assert_eq!((ana.wins, ana.streak, ana.letters), (1, 1, 2));
+        let luis = store.get("2").unwrap();
+        assert_eq!((luis.wins, luis.streak, luis.best_streak), (2, 0, 2));
+
+        // only the players of the round lose their streak
+        store.record("2", "luis", GuessResult::Solved, 10);
+        store.end_round(Some("2"));
+        assert_eq!(store.get("1").unwrap().streak, 1);
+    }
+
+    #[tokio::test]
+    async fn save_and_load() {
+        let dir = TestDir::new("stats");
+        let path = dir.path("stats.toml");
+        let mut store = StatsStore::load(&path).await.unwrap();
+        store.players.clear();
+        store.record("123", "ana", GuessResult::Solved, 10);
+        store.end_round(Some("123"));
+        store.save().await.unwrap();
+
+        let loaded = StatsStore::load(&path).await.unwrap();
+        assert_eq!(loaded.get("123"), store.get("123"));
+    }
+}
@@ -0,0 +1,24 @@
+//! Folders for the tests that write files
+use std::path::{Path, PathBuf};
+
+/// Folder of its own in the temporary directory, so parallel runs do not share files.
+/// It is removed when dropped
+pub struct TestDir(PathBuf);
+
+impl TestDir {
+    pub fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("colgado-{name}-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("Test folder not created");
+        Self(dir)
+    }
+
+    pub fn path(&self, file: impl AsRef<Path>) -> PathBuf {
+        self.0.join(file)
+    }
+}
+
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
@@ -0,0 +1,144 @@
+//! Time limit of the rounds, with letters revealed as hints while it runs
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+
+use crate::errors::InvalidValue;
+
+/// `[game.timer]` section of `env.toml`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct TimerConfig {
+    pub enabled: bool,
+    /// Length of the round, the round is lost when it runs out
+    pub round_seconds: u64,
+    /// A letter is revealed every time this passes, 0 disables the hints
+    pub hint_seconds: u64,
+    /// Remaining seconds announced in the chat
+    pub warnings: Vec<u64>,
+}
+
+impl Default for TimerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            round_seconds: 180,
+            hint_seconds: 60,
+            warnings: vec![60, 30, 10],
+        }
+    }
+}
+
+impl TimerConfig {
+    pub fn validate(&self) -> Result<(), InvalidValue> {
+        if self.round_seconds == 0 {
+            return Err(InvalidValue::new(
+                "game.timer.round-seconds",
+                "must be greater than 0",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// What the timer asks the game to do
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimerEvent {
+    Hint,
+    /// Seconds left in the round
+    Warning(u64),
+    TimeOut,
+}
+
+#[derive(Clone, Debug)]
+pub struct RoundTimer {
+    deadline: Instant,
+    hint_interval: Option<Duration>,
+    next_hint: Option<Instant>,
+    /// Warnings not announced yet, from the latest to the earliest
+    warnings: Vec<u64>,
+}
+
+impl RoundTimer {
+    pub fn start(config: &TimerConfig, now: Instant) -> Self {
+        let hint_interval =
+            (config.hint_seconds > 0).then(|| Duration::from_secs(config.hint_seconds));
+        let mut warnings: Vec<u64> = config
+            .warnings
+            .iter()
+            .copied()
+            .filter(|seconds| *seconds > 0 && *seconds < config.round_seconds)
+            .collect();
+        warnings.sort_unstable();
+        warnings.dedup();
+        Self {
+            deadline: now + Duration::from_secs(config.round_seconds),
+            hint_interval,
+            next_hint: hint_interval.map(|interval| now + interval),
+            warnings,
+        }
+    }
+
+    /// Whole seconds left, rounded up so 0 is only shown once the time is over
+    pub fn remaining_seconds(&self, now: Instant) -> u64 {
+        let remaining = self.deadline.saturating_duration_since(now);
+        remaining.as_millis().div_ceil(1000) as u64
+    }
+
+    /// Events due at `now`, after the time out there are no more events
+    pub fn poll(&mut self, now: Instant) -> Vec<TimerEvent> {
+        if now >= self.deadline {
+            self.next_hint = None;
+            self.warnings.clear();
+            return vec![TimerEvent::TimeOut];
+        }
+        let mut events = Vec::new();
+        while let (Some(next_hint), Some(interval)) = (self.next_hint, self.hint_interval) {
+            if next_hint > now {
+                break;
+            }
+            events.push(TimerEvent::Hint);
+            self.next_hint = Some(next_hint + interval);
+        }
+        // only the closest warning is announced when several are passed at once
+        let remaining = self.remaining_seconds(now);
+        let passed = self
+            .warnings
+            .partition_point(|seconds| *seconds < remaining);
+        if let Some(seconds) = self.warnings.drain(passed..).next() {
+            events.push(TimerEvent::Warning(seconds));
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_in_order() {
+        let config = TimerConfig {
+            round_seconds: 100,
+            hint_seconds: 40,
+            warnings: vec![10, 30, 200],
+            ..TimerConfig::default()
+        };
+        let start = Instant::now();
+        let at = |seconds| start + Duration::from_secs(seconds);
+        let mut timer = RoundTimer::start(&config, start);
+
+        assert_eq!(timer.poll(at(10)), []);
+        assert_eq!(timer.poll(at(40)), [TimerEvent::Hint]);
+        assert_eq!(timer.poll(at(70)), [TimerEvent::Warning(30)]);
+        assert_eq!(timer.remaining_seconds(at(70)), 30);
+        assert_eq!(
+            timer.poll(at(95)),
+            [TimerEvent::Hint, TimerEvent::Warning(10)]
+        );
+        assert_eq!(timer.poll(at(99)), []);
+        assert_eq!(timer.poll(at(100)), [TimerEvent::TimeOut]);
+        assert_eq!(timer.remaining_seconds(at(100)), 0);
+    }
+}
@@ -1,71 +1,307 @@
+use std::collections::HashSet;
+
 use crate::errors::GameError;
-use std::ops::Deref;
+use rand::seq::IndexedRandom;
+use serde::{Deserialize, Serialize};
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
 use unicode_segmentation::UnicodeSegmentation;
 
+/// Failed guesses allowed when no other value is configured
+pub const DEFAULT_LIVES: usize = 6;
+
+/// Lives lost by a wrong whole-word guess when no other value is configured
+pub const DEFAULT_SOLVE_PENALTY: usize = 2;
+
+/// What happened after a guess
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GuessResult {
+    /// The letter is in the word, with the number of graphemes revealed
+    LetterFound(usize),
+    /// The letter is not in the word, a life is lost
+    LetterMissed,
+    /// The whole word was guessed, the game is won
+    Solved,
+    /// The whole word was wrong, the penalty is applied
+    WrongSolve,
+    /// Repeated guess or finished game, nothing changes
+    Ignored,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GameOutcome {
+    #[default]
+    InProgress,
+    Won,
+    Lost,
+}
+
+/// How guesses and word graphemes are compared, case is always ignored
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Normalization {
+    /// Treat "ó" and "o" as the same letter
+    pub fold_diacritics: bool,
+    /// Keep "ñ" distinct from "n" even when diacritics are folded
+    pub keep_enie: bool,
+}
+
+impl Normalization {
+    /// Returns the key used to compare a grapheme
+    pub fn key(&self, grapheme: &str) -> String {
+        let lowercase: String = grapheme.nfc().flat_map(char::to_lowercase).collect();
+        if !self.fold_diacritics {
+            return lowercase;
+        }
+        let mut key = String::with_capacity(lowercase.len());
+        for character in lowercase.chars() {
+            if self.keep_enie && character == 'ñ' {
+                key.push(character);
+                continue;
+            }
+            key.extend(character.nfd().filter(|c| !is_combining_mark(*c)));
+        }
+        key
+    }
+}
+
+impl Default for Normalization {
+    fn default() -> Self {
+        Self {
+            fold_diacritics: true,
+            keep_enie: true,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Game {
     characters: Vec<String>,
+    keys: Vec<String>,
+    normalization: Normalization,
     progress: Vec<bool>,
     tried: Vec<String>,
+    tried_words: Vec<String>,
     cont: usize,
+    lives: usize,
+    failures: usize,
+    solve_penalty: usize,
+    /// The time limit of the round ran out
+    timed_out: bool,
+    /// The round was ended before finishing it
+    ended: bool,
 }
 impl Game {
-    pub fn new(mut word: String) -> Self {
-        word.retain(|character| !character.is_whitespace());
+    pub fn new(word: String) -> Self {
+        let word = word.split_whitespace().collect::<Vec<_>>().join(" ");
         let characters: Vec<String> = word
             .graphemes(true)
             .map(|string| string.to_owned())
             .collect();
-        let progress: Vec<bool> = vec![false; characters.len()];
-        let cont = characters.len();
+        let normalization = Normalization::default();
+        let keys = Self::keys(&characters, &normalization);
+        // spaces and punctuation are shown from the start
+        let progress: Vec<bool> = characters
+            .iter()
+            .map(|character| !Self::is_guessable(character))
+            .collect();
+        let cont = progress.iter().filter(|revealed| !**revealed).count();
 
         Self {
             characters,
+            keys,
+            normalization,
             progress,
             tried: Vec::new(),
+            tried_words: Vec::new(),
             cont,
+            lives: DEFAULT_LIVES,
+            failures: 0,
+            solve_penalty: DEFAULT_SOLVE_PENALTY,
+            timed_out: false,
+            ended: false,
         }
     }
 
+    /// Sets the maximum number of failed guesses before the game is lost
+    pub fn with_lives(mut self, lives: usize) -> Self {
+        self.lives = lives;
+        self
+    }
+
+    /// Sets the lives lost by a wrong whole-word guess
+    pub fn with_solve_penalty(mut self, solve_penalty: usize) -> Self {
+        self.solve_penalty = solve_penalty;
+        self
+    }
+
+    /// Sets how guesses are matched against the word
+    pub fn with_normalization(mut self, normalization: Normalization) -> Self {
+        self.keys = Self::keys(&self.characters, &normalization);
+        self.normalization = normalization;
+        self
+    }
+
+    fn keys(characters: &[String], normalization: &Normalization) -> Vec<String> {
+        characters
+            .iter()
+            .map(|character| normalization.key(character))
+            .collect()
+    }
+
+    /// Only graphemes containing a letter can be guessed
+    pub fn is_guessable(grapheme: &str) -> bool {
+        grapheme.chars().any(char::is_alphabetic)
+    }
+
     pub fn split_chars(word: &str) -> Vec<&str> {
         UnicodeSegmentation::graphemes(word, true).collect()
     }
 
+    /// Letters to guess, spaces and punctuation are not counted
+    pub fn count_letters(word: &str) -> usize {
+        Self::split_chars(word)
+            .into_iter()
+            .filter(|grapheme| Self::is_guessable(grapheme))
+            .count()
+    }
+
+    /// A guess with a single letter reveals it, anything longer tries to solve the word
+    pub fn guess(&mut self, word_chars: &[&str]) -> Result<GuessResult, GameError> {
+        let letters: Vec<&str> = word_chars
+            .iter()
+            .copied()
+            .filter(|word_char| Self::is_guessable(word_char))
+            .collect();
+        if self.outcome() != GameOutcome::InProgress || letters.is_empty() {
+            return Ok(GuessResult::Ignored);
+        }
+        if letters.len() == 1 {
+            let failures = self.failures;
+            let found = self.check_word_chars(&letters)?;
+            let result = if found > 0 {
+                GuessResult::LetterFound(found)
+            } else if self.failures > failures {
+                GuessResult::LetterMissed
+            } else {
+                GuessResult::Ignored
+            };
+            return Ok(result);
+        }
+        self.solve(&letters)
+    }
+
+    fn solve(&mut self, letters: &[&str]) -> Result<GuessResult, GameError> {
+        let word_keys: Vec<&String> = self
+            .keys
+            .iter()
+            .zip(&self.characters)
+            .filter(|(_, character)| Self::is_guessable(character))
+            .map(|(key, _)| key)
+            .collect();
+        if letters.len() > word_keys.len() {
+            return Err(GameError::InvalidWord);
+        }
+        let keys: Vec<String> = letters
+            .iter()
+            .map(|letter| self.normalization.key(letter))
+            .collect();
+        if keys.iter().eq(word_keys) {
+            self.progress
+                .iter_mut()
+                .for_each(|revealed| *revealed = true);
+            self.cont = 0;
+            return Ok(GuessResult::Solved);
+        }
+        let attempt = keys.concat();
+        if self.tried_words.contains(&attempt) {
+            return Ok(GuessResult::Ignored);
+        }
+        self.tried_words.push(attempt);
+        self.failures += self.solve_penalty;
+        Ok(GuessResult::WrongSolve)
+    }
+
     pub fn check_word_chars(&mut self, word_chars: &[&str]) -> Result<usize, GameError> {
         let mut num = 0;
         if word_chars.len() > self.characters.len() {
             return Err(GameError::InvalidWord);
         }
         word_chars.iter().for_each(|word_char| {
-            let word_char = *word_char;
-            if self.tried.contains(&word_char.into()) {
+            if !Self::is_guessable(word_char) {
                 return;
             }
-            for (i, character) in self.characters.iter().enumerate() {
-                let character: &str = character;
-                if self.progress[i] && character == word_char {
-                    break;
-                }
-                if character == word_char {
+            let word_char = self.normalization.key(word_char);
+            if self.is_lost() || self.tried.contains(&word_char) {
+                return;
+            }
+            if !self.keys.contains(&word_char) {
+                self.tried.push(word_char);
+                self.failures += 1;
+                return;
+            }
+            for (i, key) in self.keys.iter().enumerate() {
+                if !self.progress[i] && *key == word_char {
                     self.progress[i] = true;
                     self.cont -= 1;
                     num += 1;
-                } else {
-                    match self.tried.last() {
-                        Some(tried) if tried.deref() == word_char => {}
-                        _ => {
-                            let word_char = word_char.into();
-                            if !self.characters.contains(&word_char) {
-                                self.tried.push(word_char);
-                            }
-                        }
-                    }
                 }
             }
         });
         Ok(num)
     }
 
+    /// Reveals every grapheme of a random hidden letter, never the last one so the word
+    /// is still guessed by a player. Returns the revealed grapheme
+    pub fn reveal_hint(&mut self) -> Option<String> {
+        if self.outcome() != GameOutcome::InProgress {
+            return None;
+        }
+        let hidden: HashSet<&String> = self
+            .keys
+            .iter()
+            .zip(&self.progress)
+            .filter(|(_, revealed)| !**revealed)
+            .map(|(key, _)| key)
+            .collect();
+        if hidden.len() < 2 {
+            return None;
+        }
+        let hidden: Vec<&String> = hidden.into_iter().collect();
+        let key = (*hidden.choose(&mut rand::rng())?).clone();
+        let mut revealed = None;
+        for (i, character) in self.characters.iter().enumerate() {
+            if !self.progress[i] && self.keys[i] == key {
+                self.progress[i] = true;
+                self.cont -= 1;
+                revealed.get_or_insert_with(|| character.to_lowercase());
+            }
+        }
+        revealed
+    }
+
+    /// Ends the round as lost because the time limit ran out
+    pub fn time_out(&mut self) {
+        if self.outcome() == GameOutcome::InProgress {
+            self.timed_out = true;
+        }
+    }
+
+    pub fn is_timed_out(&self) -> bool {
+        self.timed_out
+    }
+
+    /// Ends the round as lost without waiting for the guesses
+    pub fn end(&mut self) {
+        if self.outcome() == GameOutcome::InProgress {
+            self.ended = true;
+        }
+    }
+
+    pub fn is_ended(&self) -> bool {
+        self.ended
+    }
+
     pub fn get_actual_word(&self) -> String {
         let mut string = String::with_capacity(self.characters.len());
         for (i, letter) in self.characters.iter().enumerate() {
@@ -77,6 +313,14 @@ impl Game {
         string
     }
 
+    /// Actual word split at the spaces, each item is a word of the phrase
+    pub fn get_actual_words(&self) -> Vec<String> {
+        self.get_actual_word()
+            .split_whitespace()
+            .map(str::to_owned)
+            .collect()
+    }
+
     pub fn get_letters(&self) -> String {
         let size = if !self.tried.is_empty() {
             2 * self.tried.len()
@@ -91,9 +335,35 @@ impl Game {
         string
     }
 
+    pub fn get_word(&self) -> String {
+        self.characters.concat()
+    }
+
     pub fn is_completed(&self) -> bool {
         self.cont == 0
     }
+
+    pub fn max_lives(&self) -> usize {
+        self.lives
+    }
+
+    pub fn remaining_lives(&self) -> usize {
+        self.lives.saturating_sub(self.failures)
+    }
+
+    pub fn is_lost(&self) -> bool {
+        !self.is_completed() && (self.remaining_lives() == 0 || self.timed_out || self.ended)
+    }
+
+    pub fn outcome(&self) -> GameOutcome {
+        if self.is_completed() {
+            GameOutcome::Won
+        } else if self.is_lost() {
+            GameOutcome::Lost
+        } else {
+            GameOutcome::InProgress
+        }
+    }
 }
 
 #[cfg(test)]
@@ -118,7 +388,38 @@ mod tests {
     fn check_word_with_uppercase() {
         let mut word = Game::new("Prueba".to_owned());
         word.check_word_chars(&Game::split_chars("prueba")).unwrap();
-        assert_eq!(word.get_letters(), "p ");
+        assert_eq!(word.get_letters(), "");
+        assert_eq!(word.get_actual_word(), "Prueba");
+    }
+
+    #[test]
+    fn check_word_with_accents() {
+        let mut word = Game::new("Camión".to_owned());
+        assert_eq!(word.check_word_chars(&Game::split_chars("O")).unwrap(), 1);
+        assert_eq!(word.get_actual_word(), "____ó_");
+
+        let exact = Normalization {
+            fold_diacritics: false,
+            keep_enie: true,
+        };
+        let mut word = Game::new("camión".to_owned()).with_normalization(exact);
+        assert_eq!(word.check_word_chars(&Game::split_chars("o")).unwrap(), 0);
+        assert_eq!(word.get_letters(), "o ");
+    }
+
+    #[test]
+    fn check_word_with_enie() {
+        let mut word = Game::new("niño".to_owned());
+        assert_eq!(word.check_word_chars(&Game::split_chars("n")).unwrap(), 1);
+        assert_eq!(word.get_actual_word(), "n___");
+
+        let normalization = Normalization {
+            fold_diacritics: true,
+            keep_enie: false,
+        };
+        let mut word = Game::new("niño".to_owned()).with_normalization(normalization);
+        assert_eq!(word.check_word_chars(&Game::split_chars("n")).unwrap(), 2);
+        assert_eq!(word.get_actual_word(), "n_ñ_");
     }
 
     #[test]
@@ -151,5 +452,101 @@ mod tests {
         println!("{word:?}");
         assert!(word.get_letters().is_empty());
         assert!(word.is_completed());
+        assert_eq!(word.get_actual_word(), input);
+    }
+
+    #[test]
+    fn punctuation_is_revealed() {
+        let mut word = Game::new("  l'arc-en-ciel  rojo ".to_owned());
+        assert_eq!(word.get_actual_word(), "_'___-__-____ ____");
+        assert_eq!(word.get_actual_words(), vec!["_'___-__-____", "____"]);
+
+        assert_eq!(word.check_word_chars(&Game::split_chars("-' ")).unwrap(), 0);
+        assert!(word.get_letters().is_empty());
+        assert_eq!(word.remaining_lives(), DEFAULT_LIVES);
+
+        word.check_word_chars(&Game::split_chars("larcenirojo"))
+            .unwrap();
+        assert!(word.is_completed());
+        assert_eq!(word.get_actual_word(), "l'arc-en-ciel rojo");
+    }
+
+    #[test]
+    fn guess_letter() {
+        let mut word = Game::new("prueba".to_owned());
+        let result = word.guess(&Game::split_chars("e")).unwrap();
+        assert_eq!(result, GuessResult::LetterFound(1));
+        let result = word.guess(&Game::split_chars("e")).unwrap();
+        assert_eq!(result, GuessResult::Ignored);
+        let result = word.guess(&Game::split_chars("x")).unwrap();
+        assert_eq!(result, GuessResult::LetterMissed);
+        assert_eq!(word.remaining_lives(), DEFAULT_LIVES - 1);
+    }
+
+    #[test]
+    fn guess_whole_word() {
+        let mut word = Game::new("Camión rojo".to_owned());
+        let result = word.guess(&Game::split_chars("camion roja")).unwrap();
+        assert_eq!(result, GuessResult::WrongSolve);
+        assert_eq!(word.get_actual_word(), "______ ____");
+        assert!(word.get_letters().is_empty());
+        assert_eq!(
+            word.remaining_lives(),
+            DEFAULT_LIVES - DEFAULT_SOLVE_PENALTY
+        );
+
+        let result = word.guess(&Game::split_chars("camion roja")).unwrap();
+        assert_eq!(result, GuessResult::Ignored);
+        assert_eq!(
+            word.remaining_lives(),
+            DEFAULT_LIVES - DEFAULT_SOLVE_PENALTY
+        );
+
+        let result = word.guess(&Game::split_chars("camionrojo")).unwrap();
+        assert_eq!(result, GuessResult::Solved);
+        assert_eq!(word.outcome(), GameOutcome::Won);
+        assert_eq!(word.get_actual_word(), "Camión rojo");
+    }
+
+    #[test]
+    fn guess_too_long() {
+        let mut word = Game::new("rojo".to_owned());
+        assert!(word.guess(&Game::split_chars("naranja")).is_err());
+        assert_eq!(word.remaining_lives(), DEFAULT_LIVES);
+    }
+
+    #[test]
+    fn lose_after_max_failures() {
+        let mut word = Game::new("prueba".to_owned()).with_lives(3);
+        word.check_word_chars(&Game::split_chars("xy")).unwrap();
+        assert_eq!(word.remaining_lives(), 1);
+        assert_eq!(word.outcome(), GameOutcome::InProgress);
+
+        word.check_word_chars(&Game::split_chars("zp")).unwrap();
+        assert_eq!(word.remaining_lives(), 0);
+        assert_eq!(word.outcome(), GameOutcome::Lost);
+        assert_eq!(word.get_actual_word(), "______");
+    }
+
+    #[test]
+    fn hints_and_time_out() {
+        let mut word = Game::new("aab".to_owned());
+        let hint = word.reveal_hint().unwrap();
+        let expected = if hint == "a" { "aa_" } else { "__b" };
+        assert_eq!(word.get_actual_word(), expected);
+        assert_eq!(word.reveal_hint(), None);
+
+        word.time_out();
+        assert_eq!(word.outcome(), GameOutcome::Lost);
+        assert!(word.is_timed_out());
+        assert_eq!(word.remaining_lives(), DEFAULT_LIVES);
+    }
+
+    #[test]
+    fn repeated_failure_costs_one_life() {
+        let mut word = Game::new("prueba".to_owned());
+        word.check_word_chars(&Game::split_chars("x")).unwrap();
+        word.check_word_chars(&Game::split_chars("x")).unwrap();
+        assert_eq!(word.remaining_lives(), DEFAULT_LIVES - 1);
     }
 }
@@ -0,0 +1,331 @@
+//! Words grouped in categories to start rounds without typing them, one file per category
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use rand::seq::IndexedRandom;
+use serde::Deserialize;
+
+use crate::{
+    errors::{ColgadoLogicError, InvalidValue},
+    word::{Game, Normalization},
+};
+
+/// `[words]` section of `env.toml`
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct WordBankConfig {
+    /// Folder with a `.txt` or `.toml` file per category, the file name is the category
+    pub path: PathBuf,
+    /// Letters of the shortest word picked
+    pub min_length: Option<usize>,
+    /// Letters of the longest word picked
+    pub max_length: Option<usize>,
+    /// Only words of this difficulty are picked
+    pub difficulty: Option<Difficulty>,
+}
+
+impl Default for WordBankConfig {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::from("words"),
+            min_length: None,
+            max_length: None,
+            difficulty: None,
+        }
+    }
+}
+
+impl WordBankConfig {
+    pub fn validate(&self) -> Result<(), InvalidValue> {
+        if let (Some(min), Some(max)) = (self.min_length, self.max_length)
+            && min > max
+        {
+            return Err(InvalidValue::new(
+                "words.min-length",
+                "can not be greater than max-length",
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn filter(&self) -> WordFilter {
+        WordFilter {
+            min_length: self.min_length,
+            max_length: self.max_length,
+            difficulty: self.difficulty,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    /// Used for the words of the text files, more different letters need more right guesses
+    pub fn estimate(word: &str) -> Self {
+        match distinct_letters(word) {
+            0..=5 => Self::Easy,
+            6..=8 => Self::Medium,
+            _ => Self::Hard,
+        }
+    }
+}
+
+/// Conditions of the random words, `None` accepts any value
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WordFilter {
+    pub min_length: Option<usize>,
+    pub max_length: Option<usize>,
+    pub difficulty: Option<Difficulty>,
+}
+
+impl WordFilter {
+    fn matches(&self, word: &BankWord) -> bool {
+        self.min_length.is_none_or(|min| word.length >= min)
+            && self.max_length.is_none_or(|max| word.length <= max)
+            && self
+                .difficulty
+                .is_none_or(|difficulty| word.difficulty == difficulty)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BankWord {
+    pub text: String,
+    pub difficulty: Difficulty,
+    /// Letters to guess, spaces and punctuation are not counted
+    pub length: usize,
+}
+
+impl BankWord {
+    pub fn new(text: &str, difficulty: Difficulty) -> Self {
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        let length = Game::count_letters(&text);
+        Self {
+            text,
+            difficulty,
+            length,
+        }
+    }
+
+    /// Difficulty estimated from the word
+    pub fn estimated(text: &str) -> Self {
+        Self::new(text, Difficulty::estimate(text))
+    }
+}
+
+/// Word picked from the bank
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RandomWord {
+    pub word: String,
+    pub category: String,
+}
+
+/// Contents of a `.toml` category file
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct CategoryFile {
+    /// Shown instead of the file name
+    name: Option<String>,
+    easy: Vec<String>,
+    medium: Vec<String>,
+    hard: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct WordBank {
+    categories: BTreeMap<String, Vec<BankWord>>,
+    filter: WordFilter,
+    /// Words already played in this session
+    used: HashSet<String>,
+}
+
+impl WordBank {
+    /// Reads every category of the folder, a missing folder is an empty bank
+    pub async fn load(config: &WordBankConfig) -> Result<Self, ColgadoLogicError> {
+        let mut bank = Self::default().with_filter(config.filter());
+        let io_error = |path: &Path, err| ColgadoLogicError::WordBankIo {
+            path: path.to_owned(),
+            err,
+        };
+        let mut entries = match tokio::fs::read_dir(&config.path).await {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(bank),
+            Err(err) => return Err(io_error(&config.path, err)),
+        };
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .map_err(|err| io_error(&config.path, err))?
+        {
+            let path = entry.path();
+            let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            let is_toml = match path.extension().and_then(|extension| extension.to_str()) {
+                Some("toml") => true,
+                Some("txt") => false,
+                _ => continue,
+            };
+            let text = tokio::fs::read_to_string(&path)
+                .await
+                .map_err(|err| io_error(&path, err))?;
+            let (name, words) = if is_toml {
+                parse_toml(&text).map_err(|err| ColgadoLogicError::WordBankFormat {
+                    path: path.clone(),
+                    err,
+                })?
+            } else {
+                (None, parse_text(&text))
+            };
+            bank.add_category(name.unwrap_or_else(|| stem.to_owned()), words);
+        }
+        Ok(bank)
+    }
+
+    pub fn with_filter(mut self, filter: WordFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Adds the words to the category, creating it when new
+    pub fn add_category(&mut self, name: String, words: Vec<BankWord>) {
+        self.categories.entry(name).or_default().extend(words);
+    }
+
+    /// Names of the categories in alphabetical order
+    pub fn categories(&self) -> Vec<String> {
+        self.categories.keys().cloned().collect()
+    }
+
+    /// Name of the category, written in any case
+    pub fn category(&self, name: &str) -> Option<&str> {
+        let name = name.to_lowercase();
+        self.categories
+            .keys()
+            .find(|category| category.to_lowercase() == name)
+            .map(String::as_str)
+    }
+
+    /// Random word passing the filter and not played yet, from any category when `None`.
+    /// `playable` tells the words the rules of the game accept
+    pub fn pick(
+        &mut self,
+        category: Option<&str>,
+        playable: impl Fn(&str) -> bool,
+    ) -> Option<RandomWord> {
+        let normalization = Normalization::default();
+        let candidates: Vec<(&String, &BankWord)> = self
+            .categories
+            .iter()
+            .filter(|(name, _)| category.is_none_or(|category| category == name.as_str()))
+            .flat_map(|(name, words)| words.iter().map(move |word| (name, word)))
+            .filter(|(_, word)| self.filter.matches(word) && playable(&word.text))
+            .filter(|(_, word)| !self.used.contains(&normalization.key(&word.text)))
+            .collect();
+        let (category, word) = candidates.choose(&mut rand::rng())?;
+        let random_word = RandomWord {
+            word: word.text.clone(),
+            category: (*category).clone(),
+        };
+        self.used.insert(normalization.key(&random_word.word));
+        Some(random_word)
+    }
+}
+
+/// One word per line, empty lines and lines starting with `#` are skipped
+fn parse_text(text: &str) -> Vec<BankWord> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(BankWord::estimated)
+        .collect()
+}
+
+fn parse_toml(text: &str) -> Result<(Option<String>, Vec<BankWord>), toml::de::Error> {
+    let file: CategoryFile = toml::from_str(text)?;
+    let levels = [
+        (file.easy, Difficulty::Easy),
+        (file.medium, Difficulty::Medium),
+        (file.hard, Difficulty::Hard),
+    ];
+    let words = levels
+        .into_iter()
+        .flat_map(|(words, difficulty)| {
+            words
+                .into_iter()
+                .map(move |word| BankWord::new(&word, difficulty))
+        })
+        .collect();
+    Ok((file.name, words))
+}
+
+fn distinct_letters(word: &str) -> usize {
+    let normalization = Normalization::default();
+    Game::split_chars(word)
+        .into_iter()
+        .filter(|grapheme| Game::is_guessable(grapheme))
+        .map(|grapheme| normalization.key(grapheme))
+        .collect::<HashSet<_>>()
+        .len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn category_files() {
+        let words = parse_text("# animales\ngato\n\n  murciélago \n");
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[0].difficulty, Difficulty::Easy);
+        assert_eq!(words[1].text, "murciélago");
+        assert_eq!(words[1].length, 10);
+        assert_eq!(words[1].difficulty, Difficulty::Hard);
+
+        let (name, words) = parse_toml(
+            r#"
+            name = "Películas"
+            easy = ["Up"]
+            hard = ["El padrino"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(name.as_deref(), Some("Películas"));
+        assert_eq!(words[1], BankWord::new("El padrino", Difficulty::Hard));
+        assert_eq!(words[1].length, 9);
+    }
+
+    #[test]
+    fn picks_without_repeats() {
+        let mut bank = WordBank::default().with_filter(WordFilter {
+            max_length: Some(5),
+            ..WordFilter::default()
+        });
+        let words = ["gato", "perro", "elefante"].map(BankWord::estimated);
+        bank.add_category("animales".to_owned(), words.to_vec());
+        bank.add_category("colores".to_owned(), vec![BankWord::estimated("rojo")]);
+
+        let mut picked: Vec<String> = (0..2)
+            .map(|_| bank.pick(Some("animales"), |_| true).unwrap().word)
+            .collect();
+        picked.sort();
+        assert_eq!(picked, ["gato", "perro"]);
+        assert_eq!(bank.pick(Some("animales"), |_| true), None);
+
+        assert_eq!(bank.pick(None, |word| word.len() < 4), None);
+        let word = bank.pick(None, |_| true).unwrap();
+        assert_eq!(
+            (word.word.as_str(), word.category.as_str()),
+            ("rojo", "colores")
+        );
+        assert_eq!(bank.pick(None, |_| true), None);
+    }
+}
@@ -0,0 +1,302 @@
+use std::{collections::VecDeque, sync::Arc};
+
+use colgado_logic::{
+    i18n::Catalog,
+    models::{
+        events::{ConnectionStatus, GameEvent, GuessResult},
+        game_view::GameView,
+        handles::Handles,
+        leaderboard::Leaderboard,
+    },
+};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use tokio::task::JoinHandle;
+
+/// Lines kept in the log of the round, with the ones of the actors
+const LOG_LINES: usize = 8;
+
+pub type Connected = (Handles, Arc<[JoinHandle<()>]>);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum State {
+    NewConnection,
+    Connecting,
+    NewWord,
+    Playing,
+    GameCompleted,
+    /// The actors can not continue, a new connection is needed
+    Error,
+}
+
+/// Work that needs the actors, done by the main loop
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Action {
+    Connect,
+    SetWord(String),
+}
+
+pub struct App {
+    pub state: State,
+    /// Word being typed, only shown while `peek` is set
+    pub word: String,
+    pub peek: bool,
+    pub game: Option<GameView>,
+    pub leaderboard: Leaderboard,
+    pub connection: Option<ConnectionStatus>,
+    pub error: Option<String>,
+    pub log: VecDeque<String>,
+    pub handles: Option<Handles>,
+    tasks: Option<Arc<[JoinHandle<()>]>>,
+    /// Texts of the terminal
+    pub ui: Catalog,
+    pub exit: bool,
+}
+
+impl App {
+    pub fn new(ui: Catalog) -> Self {
+        Self {
+            ui,
+            ..Self::default()
+        }
+    }
+
+    pub fn handle_terminal_event(&mut self, event: Event) -> Option<Action> {
+        match event {
+            Event::Key(key) if key.kind == KeyEventKind::Press => self.handle_key(key),
+            _ => None,
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Option<Action> {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.exit = true;
+            return None;
+        }
+        match (self.state, key.code) {
+            (State::NewWord, KeyCode::Char(c)) => self.word.push(c),
+            (State::NewWord, KeyCode::Backspace) => {
+                self.word.pop();
+            }
+            (State::NewWord, KeyCode::Tab) => self.peek = !self.peek,
+            (State::NewWord, KeyCode::Esc) => self.word.clear(),
+            (State::NewWord, KeyCode::Enter) if !self.word.trim().is_empty() => {
+                return Some(Action::SetWord(self.word.trim().to_owned()));
+            }
+            (_, KeyCode::Esc | KeyCode::Char('q')) => self.exit = true,
+            (State::NewConnection, KeyCode::Enter) => {
+                self.state = State::Connecting;
+                return Some(Action::Connect);
+            }
+            (State::Error, KeyCode::Enter) => {
+                self.abort_tasks();
+                *self = Self {
+                    leaderboard: std::mem::take(&mut self.leaderboard),
+                    state: State::Connecting,
+                    ..Self::new(self.ui)
+                };
+                return Some(Action::Connect);
+            }
+            (State::GameCompleted, KeyCode::Char('n')) => {
+                self.state = State::NewWord;
+                self.game = None;
+                self.log.clear();
+            }
+            _ => {}
+        }
+        None
+    }
+
+    pub fn connected(&mut self, (handles, tasks): Connected) {
+        self.state = State::NewWord;
+        self.connection = Some(ConnectionStatus::Connected);
+        self.error = None;
+        self.handles = Some(handles);
+        self.tasks = Some(tasks);
+    }
+
+    pub fn connection_failed(&mut self, error: String) {
+        self.state = State::NewConnection;
+        self.error = Some(error);
+    }
+
+    /// Starts the round, the game actor announces it in the chat
+    pub fn word_setted(&mut self) {
+        self.state = State::Playing;
+        self.word.clear();
+        self.peek = false;
+    }
+
+    pub fn update_game(&mut self, game: Option<GameView>) {
+        self.game = game;
+        let Some(game) = &self.game else {
+            return;
+        };
+        if game.is_completed && self.state == State::Playing {
+            self.state = State::GameCompleted;
+        } else if !game.is_completed && matches!(self.state, State::NewWord | State::GameCompleted)
+        {
+            // the moderators can start a round from the chat
+            self.state = State::Playing;
+        }
+    }
+
+    pub fn handle_game_event(&mut self, event: GameEvent) {
+        match event {
+            GameEvent::Guess {
+                player_name,
+                result,
+            } => {
+                let player = ("player", &player_name as &dyn std::fmt::Display);
+                let line = match result {
+                    GuessResult::LetterFound(found) => self
+                        .ui
+                        .format("ui.letter-found", &[player, ("found", &found)]),
+                    GuessResult::LetterMissed => self.ui.format("ui.letter-missed", &[player]),
+                    GuessResult::Solved => self.ui.format("ui.solved", &[player]),
+                    GuessResult::WrongSolve => self.ui.format("ui.wrong-solve", &[player]),
+                    GuessResult::Ignored => return,
+                };
+                self.push_log(line);
+            }
+            GameEvent::RoundFinished { .. } => {}
+            GameEvent::LeaderboardChanged(leaderboard) => self.leaderboard = leaderboard,
+            GameEvent::ConnectionChanged(status) => self.connection = Some(status),
+            GameEvent::Error(err) => {
+                if err.is_fatal() {
+                    self.state = State::Error;
+                }
+                self.error = Some(err.to_string());
+            }
+        }
+    }
+
+    pub fn show_error(&mut self, error: String, fatal: bool) {
+        if fatal {
+            self.state = State::Error;
+        }
+        self.error = Some(error);
+    }
+
+    pub fn push_log(&mut self, line: String) {
+        if self.log.len() == LOG_LINES {
+            self.log.pop_front();
+        }
+        self.log.push_back(line);
+    }
+
+    pub fn abort_tasks(&self) {
+        if let Some(tasks) = &self.tasks {
+            tasks.iter().for_each(|task| {
+                if !task.is_finished() {
+                    task.abort();
+                }
+            });
+        }
+    }
+}
+
+impl Default for App {
+    fn default() -> Self {
+        Self {
+            state: State::NewConnection,
+            word: String::new(),
+            peek: false,
+            game: None,
+            leaderboard: Leaderboard::default(),
+            connection: None,
+            error: None,
+            log: VecDeque::with_capacity(LOG_LINES),
+            handles: None,
+            tasks: None,
+            ui: Catalog::default(),
+            exit: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(app: &mut App, code: KeyCode) -> Option<Action> {
+        app.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    fn type_word(app: &mut App, word: &str) {
+        word.chars().for_each(|c| {
+            press(app, KeyCode::Char(c));
+        });
+    }
+
+    #[test]
+    fn submits_the_word() {
+        let mut app = App {
+            state: State::NewWord,
+            ..App::default()
+        };
+        assert_eq!(press(&mut app, KeyCode::Enter), None);
+
+        type_word(&mut app, "casaq");
+        press(&mut app, KeyCode::Backspace);
+        press(&mut app, KeyCode::Tab);
+        assert!(app.peek);
+        assert_eq!(
+            press(&mut app, KeyCode::Enter),
+            Some(Action::SetWord("casa".to_owned()))
+        );
+        assert!(!app.exit);
+
+        app.word_setted();
+        assert_eq!(app.state, State::Playing);
+        assert!(app.word.is_empty() && !app.peek);
+    }
+
+    #[test]
+    fn quits() {
+        let mut app = App::default();
+        assert_eq!(press(&mut app, KeyCode::Enter), Some(Action::Connect));
+        assert_eq!(app.state, State::Connecting);
+        press(&mut app, KeyCode::Char('q'));
+        assert!(app.exit);
+
+        // while typing the word only ctrl-c quits
+        let mut app = App {
+            state: State::NewWord,
+            ..App::default()
+        };
+        type_word(&mut app, "q");
+        press(&mut app, KeyCode::Esc);
+        assert!(!app.exit && app.word.is_empty());
+        app.handle_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL));
+        assert!(app.exit);
+    }
+
+    #[test]
+    fn updates_the_game() {
+        let mut app = App {
+            state: State::Playing,
+            ..App::default()
+        };
+        app.update_game(Some(GameView::new(
+            "c_s_".to_owned(),
+            "cs".to_owned(),
+            false,
+        )));
+        assert_eq!(app.state, State::Playing);
+        assert_eq!(app.game.as_ref().unwrap().word, "c_s_");
+
+        app.update_game(Some(GameView::new(
+            "casa".to_owned(),
+            "acs".to_owned(),
+            true,
+        )));
+        assert_eq!(app.state, State::GameCompleted);
+        press(&mut app, KeyCode::Char('n'));
+        assert_eq!(app.state, State::NewWord);
+        assert!(app.game.is_none());
+
+        // a round started from the chat
+        app.update_game(Some(GameView::new("____".to_owned(), String::new(), false)));
+        assert_eq!(app.state, State::Playing);
+    }
+}
@@ -0,0 +1,137 @@
+mod app;
+mod ui;
+
+use std::io;
+
+use app::{Action, App, Connected};
+use colgado_logic::{
+    config::{Config, CONFIG_FILE},
+    errors::ColgadoLogicError,
+    i18n::Catalog,
+    models::{events::GameEvent, game_view::GameView},
+};
+use crossterm::event::EventStream;
+use futures_util::StreamExt;
+use ratatui::DefaultTerminal;
+use tokio::{
+    sync::{broadcast, broadcast::error::RecvError, mpsc, watch},
+    task::{JoinError, JoinHandle},
+};
+
+#[tokio::main]
+async fn main() -> io::Result<()> {
+    // printing would break the screen, the lines are shown in the log
+    let mut logs = colgado_logic::log::capture();
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, &mut logs).await;
+    ratatui::restore();
+    // the ones written while closing
+    while let Ok(line) = logs.try_recv() {
+        eprintln!("{line}");
+    }
+    result
+}
+
+async fn run(
+    terminal: &mut DefaultTerminal,
+    logs: &mut mpsc::UnboundedReceiver<String>,
+) -> io::Result<()> {
+    // a missing or invalid file is reported when connecting
+    let config = Config::load(CONFIG_FILE).await.unwrap_or_default();
+    let mut app = App::new(Catalog::new(config.ui_language()));
+    let mut terminal_events = EventStream::new();
+    let mut connecting: Option<JoinHandle<Result<Connected, ColgadoLogicError>>> = None;
+    let mut game_states: Option<watch::Receiver<Option<GameView>>> = None;
+    let mut game_events: Option<broadcast::Receiver<GameEvent>> = None;
+
+    while !app.exit {
+        terminal.draw(|frame| ui::draw(frame, &app))?;
+        tokio::select! {
+            event = terminal_events.next() => match event {
+                Some(Ok(event)) => match app.handle_terminal_event(event) {
+                    Some(Action::Connect) => {
+                        connecting = Some(tokio::spawn(colgado_logic::init_flow()));
+                    }
+                    Some(Action::SetWord(word)) => set_word(&mut app, word).await,
+                    None => {}
+                },
+                Some(Err(err)) => return Err(err),
+                None => break,
+            },
+            result = join(&mut connecting) => {
+                // the login of trequests writes in the terminal
+                terminal.clear()?;
+                match result {
+                    Ok(Ok(connected)) => {
+                        let game_handle = &connected.0.game_handle;
+                        game_states = Some(game_handle.watch_game_state());
+                        game_events = Some(game_handle.subscribe_events());
+                        app.connected(connected);
+                    }
+                    Ok(Err(err)) => app.connection_failed(err.to_string()),
+                    Err(err) => app.connection_failed(err.to_string()),
+                }
+            },
+            Some(game) = next_state(&mut game_states) => app.update_game(game),
+            Some(event) = next_event(&mut game_events) => app.handle_game_event(event),
+            Some(line) = logs.recv() => app.push_log(line),
+        }
+    }
+    // a timeout is not worth reporting, the tasks left are aborted anyway
+    if let Some(handles) = &app.handles {
+        let _ = handles.shutdown().await;
+    }
+    app.abort_tasks();
+    Ok(())
+}
+
+async fn set_word(app: &mut App, word: String) {
+    let Some(handles) = &app.handles else {
+        return;
+    };
+    match handles.game_handle.set_game_word(word).await {
+        Ok(_) => app.word_setted(),
+        Err(err) => app.show_error(err.to_string(), err.is_fatal()),
+    }
+}
+
+/// Result of the connection, without connection waits forever
+async fn join<T>(task: &mut Option<JoinHandle<T>>) -> Result<T, JoinError> {
+    let result = match task {
+        Some(task) => task.await,
+        None => std::future::pending().await,
+    };
+    *task = None;
+    result
+}
+
+/// Next change of the game, `None` once the game actor finishes
+async fn next_state(
+    receiver: &mut Option<watch::Receiver<Option<GameView>>>,
+) -> Option<Option<GameView>> {
+    let Some(state_receiver) = receiver else {
+        return std::future::pending().await;
+    };
+    if state_receiver.changed().await.is_err() {
+        *receiver = None;
+        return None;
+    }
+    Some(state_receiver.borrow_and_update().clone())
+}
+
+/// Next event of the game, `None` once the game actor finishes
+async fn next_event(receiver: &mut Option<broadcast::Receiver<GameEvent>>) -> Option<GameEvent> {
+    let Some(events_receiver) = receiver else {
+        return std::future::pending().await;
+    };
+    loop {
+        match events_receiver.recv().await {
+            Ok(event) => return Some(event),
+            Err(RecvError::Lagged(_)) => continue,
+            Err(RecvError::Closed) => {
+                *receiver = None;
+                return None;
+            }
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use colgado_logic::{
+    i18n::Catalog,
+    models::{
+        events::ConnectionStatus,
+        game_view::{GameOutcome, GameView},
+        leaderboard::Leaderboard,
+    },
+};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::Stylize,
+    text::Line,
+    widgets::{Block, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app::{App, State};
+
+pub fn draw(frame: &mut Frame, app: &App) {
+    let [title, main, status] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Min(0),
+        Constraint::Length(3),
+    ])
+    .areas(frame.area());
+    let ui = app.ui;
+    let title_widget = Paragraph::new(ui.text("ui.title").bold())
+        .centered()
+        .block(Block::bordered());
+    frame.render_widget(title_widget, title);
+
+    match app.state {
+        State::NewConnection => draw_message(frame, main, ui.text("ui.press-enter-to-connect")),
+        State::Connecting => draw_message(frame, main, ui.text("ui.connecting")),
+        State::NewWord => draw_new_word(frame, main, app),
+        State::Playing | State::GameCompleted => draw_playing(frame, main, app),
+        State::Error => draw_message(frame, main, ui.text("ui.press-enter-to-reconnect")),
+    }
+    draw_status(frame, status, app);
+}
+
+fn draw_message(frame: &mut Frame, area: Rect, message: &str) {
+    let paragraph = Paragraph::new(message)
+        .centered()
+        .wrap(Wrap { trim: true })
+        .block(Block::bordered());
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_new_word(frame: &mut Frame, area: Rect, app: &App) {
+    let word = if app.peek {
+        app.word.clone()
+    } else {
+        "*".repeat(app.word.chars().count())
+    };
+    let ui = app.ui;
+    let lines = vec![
+        Line::from(ui.text("ui.enter-word")),
+        Line::from(format!("> {word}")).bold(),
+        Line::default(),
+        Line::from(ui.text("ui.new-word-keys")).dim(),
+    ];
+    let title = format!(" {} ", ui.text("ui.new-game"));
+    let paragraph = Paragraph::new(lines).block(Block::bordered().title(title));
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_playing(frame: &mut Frame, area: Rect, app: &App) {
+    let [game_area, side_area] =
+        Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(area);
+    let [leaderboard_area, log_area] =
+        Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(side_area);
+
+    let ui = app.ui;
+    let lines = match &app.game {
+        Some(game) => game_lines(ui, game, app.state),
+        None => vec![Line::from(ui.text("ui.waiting-game"))],
+    };
+    let paragraph = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(Block::bordered().title(format!(" {} ", ui.text("ui.game"))));
+    frame.render_widget(paragraph, game_area);
+
+    let paragraph = Paragraph::new(leaderboard_lines(ui, &app.leaderboard))
+        .block(Block::bordered().title(format!(" {} ", ui.text("ui.leaderboard"))));
+    frame.render_widget(paragraph, leaderboard_area);
+
+    let log: Vec<Line> = app
+        .log
+        .iter()
+        .map(|line| Line::from(line.as_str()))
+        .collect();
+    let paragraph = Paragraph::new(log)
+        .wrap(Wrap { trim: true })
+        .block(Block::bordered().title(format!(" {} ", ui.text("ui.chat"))));
+    frame.render_widget(paragraph, log_area);
+}
+
+fn game_lines(ui: Catalog, game: &GameView, state: State) -> Vec<Line<'_>> {
+    let words: Vec<String> = game
+        .words
+        .iter()
+        .map(|word| word.chars().map(String::from).collect::<Vec<_>>().join(" "))
+        .collect();
+    let mut lines = vec![
+        Line::from(words.join("   ")).bold(),
+        Line::default(),
+        Line::from(ui.format("ui.letters", &[("letters", &game.letters)])),
+        Line::from(ui.format(
+            "ui.lives",
+            &[("lives", &game.lives), ("max-lives", &game.max_lives)],
+        )),
+    ];
+    if let Some(seconds) = game.remaining_seconds {
+        let time = format!("{}:{:02}", seconds / 60, seconds % 60);
+        lines.push(Line::from(ui.format("ui.time", &[("time", &time)])));
+    }
+    if state == State::GameCompleted {
+        let result = match game.outcome {
+            GameOutcome::Won => ui.text("ui.won"),
+            GameOutcome::Lost if game.timed_out => ui.text("ui.timed-out"),
+            GameOutcome::Lost if game.ended => ui.text("ui.ended"),
+            GameOutcome::Lost => ui.text("ui.lost"),
+            GameOutcome::InProgress => "",
+        };
+        lines.push(Line::default());
+        lines.push(Line::from(result).bold());
+        if let (GameOutcome::Lost, Some(solution)) = (game.outcome, &game.solution) {
+            lines.push(Line::from(ui.format("ui.solution", &[("word", solution)])));
+        }
+        lines.push(Line::from(ui.text("ui.new-game-key")).dim());
+    }
+    lines
+}
+
+fn leaderboard_lines(ui: Catalog, leaderboard: &Leaderboard) -> Vec<Line<'_>> {
+    if leaderboard.is_empty() {
+        return vec![Line::from(ui.text("ui.leaderboard-empty"))];
+    }
+    leaderboard
+        .iter()
+        .take(10)
+        .enumerate()
+        .map(|(i, player)| {
+            Line::from(ui.format(
+                "ui.leaderboard-line",
+                &[
+                    ("position", &(i + 1)),
+                    ("player", &player.player_name),
+                    ("points", &player.points),
+                    ("wins", &player.wins),
+                ],
+            ))
+        })
+        .collect()
+}
+
+fn draw_status(frame: &mut Frame, area: Rect, app: &App) {
+    let ui = app.ui;
+    let connection = match app.connection {
+        Some(ConnectionStatus::Connected) => ui.text("ui.connected"),
+        Some(ConnectionStatus::Reconnecting) => ui.text("ui.reconnecting"),
+        Some(ConnectionStatus::Disconnected) => ui.text("ui.disconnected"),
+        None => ui.text("ui.no-connection"),
+    };
+    let quit = match app.state {
+        State::NewWord => ui.text("ui.quit-ctrl-c"),
+        _ => ui.text("ui.quit-q"),
+    };
+    let mut lines = vec![Line::from(format!("{connection} · {quit}"))];
+    if let Some(error) = &app.error {
+        lines.push(Line::from(ui.format("ui.error", &[("error", error)])).red());
+    }
+    frame.render_widget(Paragraph::new(lines), area);
+}